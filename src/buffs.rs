@@ -0,0 +1,76 @@
+use std::collections::BTreeMap;
+use termion::color;
+use veloren_client::Client;
+use veloren_common::comp::{self, BuffKind};
+
+/// A short tag for a buff, so that several fit on one line. Kinds without a tag of their own
+/// use their full name.
+fn buff_tag(kind: BuffKind) -> String {
+    let tag = match kind {
+        BuffKind::Regeneration => "Regen",
+        BuffKind::Saturation => "Sated",
+        BuffKind::Potion => "Potion",
+        BuffKind::CampfireHeal => "Campfire",
+        BuffKind::EnergyRegen => "EnRegen",
+        BuffKind::IncreaseMaxEnergy => "+Energy",
+        BuffKind::IncreaseMaxHealth => "+Health",
+        BuffKind::Invulnerability => "Invuln",
+        BuffKind::ProtectingWard => "Ward",
+        BuffKind::Frenzied => "Frenzy",
+        BuffKind::Hastened => "Haste",
+        BuffKind::Fortitude => "Fortitude",
+        BuffKind::Bleeding => "Bleed",
+        BuffKind::Cursed => "Curse",
+        BuffKind::Burning => "Burn",
+        BuffKind::Crippled => "Cripple",
+        BuffKind::Frozen => "Frozen",
+        BuffKind::Wet => "Wet",
+        BuffKind::Ensnared => "Snared",
+        BuffKind::Poisoned => "Poison",
+        BuffKind::Parried => "Parried",
+        BuffKind::PotionSickness => "Sick",
+        _ => return format!("{:?}", kind),
+    };
+    tag.to_string()
+}
+
+/// One entry per active buff kind: its colour, then e.g. `Burn 4s` or `Regen x2 12s`.
+/// Buffs are green and debuffs red.
+pub fn status_effects(client: &Client) -> Vec<(&'static str, String)> {
+    let Some(buffs) = client.current::<comp::Buffs>() else {
+        return Vec::new();
+    };
+    let now = client.state().get_time();
+
+    // Collapse stacks of the same kind, keeping whichever runs out last
+    let mut kinds = BTreeMap::<String, (BuffKind, usize, Option<f64>)>::new();
+    for buff in buffs.buffs.values() {
+        let remaining = buff.end_time.map(|end| (end.0 - now).max(0.0));
+        let entry = kinds
+            .entry(format!("{:?}", buff.kind))
+            .or_insert((buff.kind, 0, Some(0.0)));
+        entry.1 += 1;
+        entry.2 = match (entry.2, remaining) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            _ => None,
+        };
+    }
+
+    kinds
+        .into_values()
+        .map(|(kind, stacks, remaining)| {
+            let fg = if kind.is_buff() {
+                color::Green.fg_str()
+            } else {
+                color::Red.fg_str()
+            };
+            let stacks = if stacks > 1 {
+                format!(" x{}", stacks)
+            } else {
+                String::new()
+            };
+            let remaining = remaining.map_or(String::new(), |secs| format!(" {:.0}s", secs));
+            (fg, format!("{}{}{}", buff_tag(kind), stacks, remaining))
+        })
+        .collect()
+}
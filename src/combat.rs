@@ -0,0 +1,154 @@
+use crate::{display::Display, entity::uid_name, inventory::PANEL_SIZE};
+use std::time::{Duration, Instant};
+use termion::color;
+use vek::*;
+use veloren_client::Client;
+use veloren_common::{comp::chat::KillSource, outcome::Outcome, uid::Uid};
+
+/// How long damage numbers float over the map.
+const FLOAT_TIME: Duration = Duration::from_millis(1500);
+/// How many lines of the combat log to keep.
+const MAX_LOG_LEN: usize = 500;
+const LIST_ROWS: usize = 21;
+
+struct FloatingText {
+    pos: Vec3<f32>,
+    fg: &'static str,
+    text: String,
+    created: Instant,
+}
+
+/// Damage, heals and kills, both as numbers floating over the map and as a log.
+#[derive(Default)]
+pub struct CombatLog {
+    log: Vec<(&'static str, String)>,
+    floating: Vec<FloatingText>,
+    scroll: usize,
+}
+
+impl CombatLog {
+    fn push(&mut self, fg: &'static str, line: String) {
+        self.log.push((fg, line));
+        if self.log.len() > MAX_LOG_LEN {
+            self.log.remove(0);
+        }
+    }
+
+    fn name(client: &Client, uid: Uid) -> String {
+        let player_uid = client
+            .state()
+            .read_storage::<Uid>()
+            .get(client.entity())
+            .copied();
+        if Some(uid) == player_uid {
+            "You".to_string()
+        } else {
+            uid_name(client, uid)
+        }
+    }
+
+    /// Record a health change reported by the server.
+    pub fn on_outcome(&mut self, client: &Client, outcome: &Outcome) {
+        let Outcome::HealthChange { pos, info } = outcome else {
+            return;
+        };
+        if info.amount.abs() < 0.5 {
+            return;
+        }
+        let player_uid = client
+            .state()
+            .read_storage::<Uid>()
+            .get(client.entity())
+            .copied();
+        let by = info.by.map(|by| by.uid());
+        let fg = if info.amount > 0.0 {
+            color::Green.fg_str()
+        } else if Some(info.target) == player_uid {
+            color::Red.fg_str()
+        } else if by.is_some() && by == player_uid {
+            color::Yellow.fg_str()
+        } else {
+            color::White.fg_str()
+        };
+        let crit = if info.precise { "!" } else { "" };
+
+        self.floating.push(FloatingText {
+            pos: *pos,
+            fg,
+            text: format!("{:.0}{}", info.amount.abs(), crit),
+            created: Instant::now(),
+        });
+
+        let target = Self::name(client, info.target);
+        let line = match (by, info.amount > 0.0) {
+            (Some(by), true) => format!(
+                "{} healed {} for {:.0}",
+                Self::name(client, by),
+                target,
+                info.amount
+            ),
+            (None, true) => format!("{} healed {:.0}", target, info.amount),
+            (Some(by), false) => format!(
+                "{} hit {} for {:.0}{}",
+                Self::name(client, by),
+                target,
+                -info.amount,
+                crit
+            ),
+            (None, false) => format!("{} took {:.0} damage", target, -info.amount),
+        };
+        self.push(fg, line);
+    }
+
+    /// Record a kill from a kill message.
+    pub fn on_kill(&mut self, client: &Client, source: &KillSource, victim: Uid) {
+        let victim = Self::name(client, victim);
+        let line = match source {
+            KillSource::Player(uid, _) => {
+                format!("{} killed {}", Self::name(client, *uid), victim)
+            }
+            KillSource::NonPlayer(name, _) => format!("{} killed {}", name, victim),
+            KillSource::FallDamage => format!("{} fell to their death", victim),
+            _ => format!("{} died", victim),
+        };
+        self.push(color::Magenta.fg_str(), line);
+    }
+
+    /// Numbers to draw over the map, with how long they've been floating for.
+    pub fn floating(&mut self) -> impl Iterator<Item = (Vec3<f32>, f32, &'static str, &str)> {
+        self.floating
+            .retain(|text| text.created.elapsed() < FLOAT_TIME);
+        self.floating.iter().map(|text| {
+            let age = text.created.elapsed().as_secs_f32();
+            (text.pos, age, text.fg, text.text.as_str())
+        })
+    }
+
+    pub fn scroll(&mut self, dy: i32) {
+        self.scroll = (self.scroll as i32 + dy).max(0) as usize;
+    }
+
+    /// Draw the log, newest at the bottom. Scrolling up goes back in time.
+    pub fn draw(&mut self, display: &mut Display, pos: Vec2<u16>) {
+        let inner = PANEL_SIZE.x - 4;
+        self.scroll = self.scroll.min(self.log.len().saturating_sub(LIST_ROWS));
+        let title = if self.scroll > 0 {
+            format!("Combat log (-{})", self.scroll)
+        } else {
+            "Combat log".to_string()
+        };
+        display.draw_box(pos, PANEL_SIZE, &title);
+
+        let end = self.log.len() - self.scroll;
+        let start = end.saturating_sub(LIST_ROWS);
+        for (row, (fg, line)) in self.log[start..end].iter().enumerate() {
+            display.text((pos.x + 2, pos.y + 1 + row as u16), inner, fg, line);
+        }
+        display.text(
+            (pos.x + 2, pos.y + PANEL_SIZE.y - 2),
+            inner,
+            color::LightBlack.fg_str(),
+            "up/down scroll  H close",
+        );
+    }
+}
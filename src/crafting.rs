@@ -0,0 +1,240 @@
+use crate::{display::Display, inventory::PANEL_SIZE};
+use termion::color;
+use vek::*;
+use veloren_client::Client;
+use veloren_common::{
+    comp::{self, inventory::slot::InvSlotId, Inventory},
+    recipe::{Recipe, RecipeBookManifest, RecipeInput},
+    terrain::SpriteKind,
+    vol::ReadVol,
+};
+
+/// How far away a crafting station can be and still be used.
+const STATION_RANGE: i32 = 5;
+const LIST_ROWS: usize = 11;
+
+fn input_name(input: &RecipeInput) -> String {
+    match input {
+        RecipeInput::Item(def) => def.name().to_string(),
+        RecipeInput::Tag(tag) | RecipeInput::TagSameItem(tag) => format!("Any {:?}", tag),
+        RecipeInput::ListSameItem(defs) => defs.first().map_or("Any item".to_string(), |def| {
+            format!("{} (or similar)", def.name())
+        }),
+    }
+}
+
+/// Crafting station sprites close enough to the player to craft with.
+fn nearby_stations(client: &Client) -> Vec<(Vec3<i32>, SpriteKind)> {
+    let state = client.state();
+    let Some(player_pos) = state
+        .read_storage::<comp::Pos>()
+        .get(client.entity())
+        .map(|p| p.0)
+    else {
+        return Vec::new();
+    };
+    let terrain = state.terrain();
+    let centre = player_pos.map(|e| e.floor() as i32);
+    let range = -STATION_RANGE..=STATION_RANGE;
+
+    range
+        .clone()
+        .flat_map(|x| {
+            range
+                .clone()
+                .flat_map(move |y| range.clone().map(move |z| Vec3::new(x, y, z)))
+        })
+        .map(|offs| centre + offs)
+        .filter_map(|pos| Some((pos, terrain.get(pos).ok()?.get_sprite()?)))
+        .filter(|(_, sprite)| *sprite != SpriteKind::Empty)
+        .collect()
+}
+
+/// A request to craft a recipe, detached from the borrowed game state so that it can be sent.
+pub struct CraftRequest {
+    key: String,
+    slots: Vec<(u32, InvSlotId)>,
+    sprite: Option<(Vec3<i32>, SpriteKind)>,
+}
+
+impl CraftRequest {
+    pub fn apply(self, client: &mut Client) {
+        client.craft_recipe(&self.key, self.slots, self.sprite, 1);
+    }
+}
+
+struct Entry<'a> {
+    key: &'a String,
+    recipe: &'a Recipe,
+    station: Option<Option<(Vec3<i32>, SpriteKind)>>,
+    ingredients: Result<Vec<(u32, InvSlotId)>, Vec<(&'a RecipeInput, u32)>>,
+}
+
+impl Entry<'_> {
+    fn craftable(&self) -> bool {
+        self.station.is_some() && self.ingredients.is_ok()
+    }
+}
+
+/// Cursor and filter state for the crafting screen.
+#[derive(Default)]
+pub struct CraftingPanel {
+    cursor: usize,
+    craftable_only: bool,
+    status: Option<String>,
+}
+
+impl CraftingPanel {
+    fn entries<'a>(
+        &self,
+        inv: &'a Inventory,
+        rbm: &'a RecipeBookManifest,
+        stations: &[(Vec3<i32>, SpriteKind)],
+    ) -> Vec<Entry<'a>> {
+        let mut entries = inv
+            .available_recipes_iter(rbm)
+            .map(|(key, recipe)| Entry {
+                key,
+                recipe,
+                // `Some(None)` means no station is needed
+                station: match recipe.craft_sprite {
+                    Some(kind) => stations.iter().find(|(_, s)| *s == kind).map(|s| Some(*s)),
+                    None => Some(None),
+                },
+                ingredients: recipe.inventory_contains_ingredients(inv, 1),
+            })
+            .filter(|entry| !self.craftable_only || entry.craftable())
+            .collect::<Vec<_>>();
+        entries.sort_by(|a, b| a.key.cmp(b.key));
+        entries
+    }
+
+    pub fn move_cursor(&mut self, dy: i32) {
+        self.cursor = (self.cursor as i32 + dy).max(0) as usize;
+    }
+
+    pub fn toggle_filter(&mut self) {
+        self.craftable_only = !self.craftable_only;
+        self.cursor = 0;
+    }
+
+    /// Try to craft the highlighted recipe.
+    pub fn craft(&mut self, client: &Client) -> Option<CraftRequest> {
+        let stations = nearby_stations(client);
+        let inventories = client.state().read_storage::<comp::Inventory>();
+        let inv = inventories.get(client.entity())?;
+        let rbm = RecipeBookManifest::load().read();
+        let entries = self.entries(inv, &rbm, &stations);
+        let entry = entries.get(self.cursor)?;
+
+        let name = entry.recipe.output.0.name().to_string();
+        match (&entry.station, &entry.ingredients) {
+            (None, _) => {
+                self.status = Some(format!(
+                    "Needs a {:?} nearby",
+                    entry.recipe.craft_sprite.unwrap_or(SpriteKind::Empty)
+                ));
+                None
+            }
+            (_, Err(_)) => {
+                self.status = Some(format!("Missing ingredients for {}", name));
+                None
+            }
+            (Some(sprite), Ok(slots)) => {
+                self.status = Some(format!("Crafting {}", name));
+                Some(CraftRequest {
+                    key: entry.key.clone(),
+                    slots: slots.clone(),
+                    sprite: *sprite,
+                })
+            }
+        }
+    }
+
+    pub fn draw(&mut self, display: &mut Display, pos: Vec2<u16>, client: &Client) {
+        let inner = PANEL_SIZE.x - 4;
+        let stations = nearby_stations(client);
+        let inventories = client.state().read_storage::<comp::Inventory>();
+        let Some(inv) = inventories.get(client.entity()) else {
+            return;
+        };
+        let rbm = RecipeBookManifest::load().read();
+        let entries = self.entries(inv, &rbm, &stations);
+        self.cursor = self.cursor.min(entries.len().saturating_sub(1));
+
+        let title = if self.craftable_only {
+            format!("Crafting ({} craftable)", entries.len())
+        } else {
+            format!("Crafting ({} known)", entries.len())
+        };
+        display.draw_box(pos, PANEL_SIZE, &title);
+
+        let scroll = self.cursor.saturating_sub(LIST_ROWS - 1);
+        for (i, entry) in entries.iter().enumerate().skip(scroll).take(LIST_ROWS) {
+            let (mark, fg) = match (&entry.station, &entry.ingredients) {
+                (Some(_), Ok(_)) => ('+', color::Green.fg_str()),
+                (None, Ok(_)) => ('s', color::Yellow.fg_str()),
+                _ => ('-', color::LightBlack.fg_str()),
+            };
+            let line = format!("{} {}", mark, entry.recipe.output.0.name());
+            let fg = if i == self.cursor {
+                format!("{}{}", color::Bg(color::Blue), fg)
+            } else {
+                fg.to_string()
+            };
+            display.text(
+                (pos.x + 2, pos.y + 1 + (i - scroll) as u16),
+                inner,
+                &fg,
+                &line,
+            );
+        }
+
+        // Ingredients and requirements of the highlighted recipe
+        let mut lines = Vec::new();
+        if let Some(entry) = entries.get(self.cursor) {
+            let (output, amount) = &entry.recipe.output;
+            lines.push((
+                color::White.fg_str(),
+                format!("Makes {}x {}", amount, output.name()),
+            ));
+            if let Some(kind) = entry.recipe.craft_sprite {
+                let fg = if entry.station.is_some() {
+                    color::Green.fg_str()
+                } else {
+                    color::Red.fg_str()
+                };
+                lines.push((fg, format!("Station: {:?}", kind)));
+            }
+            let missing = entry.ingredients.as_ref().err();
+            for (input, amount, _) in &entry.recipe.inputs {
+                let is_missing = missing.map_or(false, |missing| {
+                    missing.iter().any(|(m, _)| std::ptr::eq(*m, input))
+                });
+                let fg = if is_missing {
+                    color::Red.fg_str()
+                } else {
+                    color::Reset.fg_str()
+                };
+                lines.push((fg, format!(" {}x {}", amount, input_name(input))));
+            }
+        } else {
+            lines.push((color::LightBlack.fg_str(), "No recipes".to_string()));
+        }
+        for (i, (fg, line)) in lines.iter().take(8).enumerate() {
+            let y = pos.y + 2 + LIST_ROWS as u16 + i as u16;
+            display.text((pos.x + 2, y), inner, fg, line);
+        }
+
+        if let Some(status) = &self.status {
+            let y = pos.y + PANEL_SIZE.y - 3;
+            display.text((pos.x + 2, y), inner, color::Green.fg_str(), status);
+        }
+        display.text(
+            (pos.x + 2, pos.y + PANEL_SIZE.y - 2),
+            inner,
+            color::LightBlack.fg_str(),
+            "up/down move  c craft  f filter  C close",
+        );
+    }
+}
@@ -0,0 +1,95 @@
+use crate::{display::Display, entity::uid_name};
+use termion::color;
+use vek::*;
+use veloren_client::Client;
+use veloren_common::{
+    comp::{self, chat::KillSource},
+    uid::Uid,
+};
+
+const OVERLAY_SIZE: Vec2<u16> = Vec2::new(40, 6);
+
+/// Tracks whether the player is dead, what killed them and where.
+#[derive(Default)]
+pub struct Death {
+    dead: bool,
+    killer: Option<String>,
+    location: Option<Vec3<f32>>,
+}
+
+impl Death {
+    /// Notice the player dying or respawning.
+    pub fn update(&mut self, client: &Client) {
+        let dead = client
+            .current::<comp::Health>()
+            .map_or(false, |health| health.is_dead);
+        if dead && !self.dead {
+            self.location = client.current::<comp::Pos>().map(|pos| pos.0);
+        } else if !dead && self.dead {
+            self.killer = None;
+        }
+        self.dead = dead;
+    }
+
+    /// Remember who killed us from a kill message, returning a line for the chat log if it was
+    /// about us.
+    pub fn on_kill(&mut self, client: &Client, source: &KillSource, victim: Uid) -> Option<String> {
+        let player_uid = client
+            .state()
+            .read_storage::<Uid>()
+            .get(client.entity())
+            .copied();
+        if Some(victim) != player_uid {
+            return None;
+        }
+        let killer = match source {
+            KillSource::Player(uid, _) => uid_name(client, *uid),
+            KillSource::NonPlayer(name, _) => name.clone(),
+            KillSource::FallDamage => "a fall".to_string(),
+            KillSource::Suicide => "yourself".to_string(),
+            _ => "unknown causes".to_string(),
+        };
+        let line = format!("You were killed by {}", killer);
+        self.killer = Some(killer);
+        Some(line)
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.dead
+    }
+
+    /// Where the player last died, to mark on the map.
+    pub fn location(&self) -> Option<Vec3<f32>> {
+        self.location
+    }
+
+    /// Draw the death notice over the middle of the map.
+    pub fn draw(&self, display: &mut Display, screen_size: Vec2<u16>) {
+        if !self.dead {
+            return;
+        }
+        let pos = (screen_size - OVERLAY_SIZE) / 2;
+        let inner = OVERLAY_SIZE.x - 4;
+        display.draw_box(pos, OVERLAY_SIZE, "You died");
+        let killer = match &self.killer {
+            Some(killer) => format!("Killed by {}", killer),
+            None => "Killed".to_string(),
+        };
+        display.text((pos.x + 2, pos.y + 1), inner, color::Red.fg_str(), &killer);
+        if let Some(location) = self.location {
+            let line = format!("at {:.0}, {:.0} (marked X)", location.x, location.y);
+            display.text(
+                (pos.x + 2, pos.y + 2),
+                inner,
+                color::LightBlack.fg_str(),
+                &line,
+            );
+        }
+        display.text(
+            (pos.x + 2, pos.y + 4),
+            inner,
+            color::Yellow.fg_str(),
+            "Press r to respawn",
+        );
+    }
+}
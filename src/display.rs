@@ -1,8 +1,9 @@
 use std::io::{self, Stdout, Write};
 use termion::{
-    clear, cursor,
+    clear, color, cursor,
     input::MouseTerminal,
     raw::{IntoRawMode, RawTerminal},
+    style,
 };
 use vek::*;
 
@@ -42,6 +43,93 @@ impl Display {
         DisplayAt(self)
     }
 
+    /// Draw a titled box, blanking out everything inside it.
+    pub fn draw_box(&mut self, pos: impl Into<Vec2<u16>>, size: impl Into<Vec2<u16>>, title: &str) {
+        let (pos, size) = (pos.into(), size.into());
+        let inner = size.x.saturating_sub(2) as usize;
+        let title = format!(
+            " {} ",
+            title
+                .chars()
+                .take(inner.saturating_sub(2))
+                .collect::<String>()
+        );
+
+        write!(
+            self.at(pos),
+            "{}/{:-^inner$}\\",
+            color::Reset.fg_str(),
+            title,
+            inner = inner
+        )
+        .unwrap();
+        for y in 1..size.y.saturating_sub(1) {
+            write!(
+                self.at((pos.x, pos.y + y)),
+                "|{:inner$}|",
+                "",
+                inner = inner
+            )
+            .unwrap();
+        }
+        write!(
+            self.at((pos.x, pos.y + size.y.saturating_sub(1))),
+            "\\{:-<inner$}/",
+            "",
+            inner = inner
+        )
+        .unwrap();
+    }
+
+    /// Write coloured text, truncated or padded to exactly `width` characters.
+    pub fn text(&mut self, pos: impl Into<Vec2<u16>>, width: u16, fg: &str, s: &str) {
+        let s = s.chars().take(width as usize).collect::<String>();
+        write!(
+            self.at(pos),
+            "{}{:width$}{}",
+            fg,
+            s,
+            style::Reset,
+            width = width as usize
+        )
+        .unwrap();
+    }
+
+    /// Draw a gauge `width` characters wide, filled with the `bg` colour in proportion to
+    /// `current / max`, with the label and numbers written over it.
+    pub fn gauge(
+        &mut self,
+        pos: impl Into<Vec2<u16>>,
+        width: u16,
+        bg: &str,
+        label: &str,
+        current: f32,
+        max: f32,
+    ) {
+        let width = width as usize;
+        let numbers = format!("{:.0}/{:.0}", current, max);
+        let text = format!(
+            " {}{:>pad$} ",
+            label,
+            numbers,
+            pad = width.saturating_sub(label.chars().count() + 2)
+        );
+        let text = text.chars().take(width).collect::<String>();
+        let fraction = if max > 0.0 { current / max } else { 0.0 };
+        let filled = ((fraction.clamp(0.0, 1.0) * width as f32).round() as usize).min(width);
+        write!(
+            self.at(pos),
+            "{}{}{}{}{}{}",
+            color::Fg(color::White),
+            bg,
+            text.chars().take(filled).collect::<String>(),
+            color::Bg(color::LightBlack),
+            text.chars().skip(filled).collect::<String>(),
+            style::Reset
+        )
+        .unwrap();
+    }
+
     pub fn flush(&mut self) {
         self.stdout.flush().unwrap();
     }
@@ -64,3 +152,34 @@ impl<'a> io::Write for DisplayAt<'a> {
         self.0.stdout.flush()
     }
 }
+
+/// Split text into lines of at most `width` characters, breaking between words where possible.
+pub fn wrap_text(s: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in s.split_whitespace() {
+        if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > width {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+        while line.chars().count() > width {
+            let rest = line.chars().skip(width).collect();
+            line = line.chars().take(width).collect();
+            lines.push(std::mem::replace(&mut line, rest));
+        }
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// A simple text gauge like `[#####-----]`, filled according to `fraction`.
+pub fn bar(fraction: f32, width: usize) -> String {
+    let inner = width.saturating_sub(2);
+    let filled = ((fraction.clamp(0.0, 1.0) * inner as f32).round() as usize).min(inner);
+    format!("[{}{}]", "#".repeat(filled), "-".repeat(inner - filled))
+}
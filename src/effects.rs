@@ -0,0 +1,105 @@
+use std::{
+    f32::consts::TAU,
+    time::{Duration, Instant},
+};
+use termion::color;
+use vek::*;
+use veloren_common::{comp::object, outcome::Outcome};
+
+/// Objects moving faster than this are drawn as projectiles.
+const PROJECTILE_SPEED: f32 = 5.0;
+const EXPLOSION_TIME: Duration = Duration::from_millis(600);
+const BEAM_TIME: Duration = Duration::from_millis(300);
+
+/// A projectile glyph pointing along its velocity, or `None` if the object isn't moving fast
+/// enough to be one. North is up on the map.
+pub fn projectile_glyph(vel: Vec3<f32>) -> Option<char> {
+    let vel = vel.xy();
+    if vel.magnitude_squared() < PROJECTILE_SPEED.powi(2) {
+        return None;
+    }
+    // Fold the direction into a half turn since `-` looks the same both ways
+    let angle = vel.y.atan2(vel.x).rem_euclid(TAU / 2.0);
+    let glyphs = ['-', '/', '|', '\\'];
+    Some(glyphs[((angle / (TAU / 8.0)).round() as usize) % glyphs.len()])
+}
+
+/// A colour for a projectile, going by what kind of object it is.
+pub fn projectile_color(body: &object::Body) -> &'static str {
+    match body {
+        object::Body::BoltFire | object::Body::BoltFireBig | object::Body::FireRainDrop => {
+            color::LightRed.fg_str()
+        }
+        object::Body::BoltIcicle | object::Body::FrostBomb => color::LightCyan.fg_str(),
+        object::Body::BoltNature | object::Body::SpitPoison => color::LightGreen.fg_str(),
+        object::Body::Arrow
+        | object::Body::ArrowSnake
+        | object::Body::ArrowTurret
+        | object::Body::ArrowClay
+        | object::Body::MultiArrow => color::White.fg_str(),
+        _ => color::LightYellow.fg_str(),
+    }
+}
+
+enum Kind {
+    Explosion { radius: f32 },
+    Beam,
+}
+
+struct Effect {
+    pos: Vec3<f32>,
+    kind: Kind,
+    created: Instant,
+}
+
+/// Short-lived markers for explosions and beam attacks.
+#[derive(Default)]
+pub struct Effects {
+    effects: Vec<Effect>,
+}
+
+impl Effects {
+    pub fn on_outcome(&mut self, outcome: &Outcome) {
+        let (pos, kind) = match outcome {
+            Outcome::Explosion { pos, radius, .. } => (*pos, Kind::Explosion { radius: *radius }),
+            Outcome::Beam { pos, .. } => (*pos, Kind::Beam),
+            _ => return,
+        };
+        self.effects.push(Effect {
+            pos,
+            kind,
+            created: Instant::now(),
+        });
+    }
+
+    /// The world positions to draw markers at, with their glyphs and colours. Explosions are a
+    /// ring around the blast, spaced so that there's about one glyph per cell at `zoom_level`.
+    pub fn markers(&mut self, zoom_level: f32) -> Vec<(Vec2<f32>, char, &'static str)> {
+        self.effects.retain(|effect| {
+            effect.created.elapsed()
+                < match effect.kind {
+                    Kind::Explosion { .. } => EXPLOSION_TIME,
+                    Kind::Beam => BEAM_TIME,
+                }
+        });
+
+        let mut markers = Vec::new();
+        for effect in &self.effects {
+            let centre = effect.pos.xy();
+            match effect.kind {
+                Kind::Explosion { radius } => {
+                    let fg = color::LightRed.fg_str();
+                    let steps = ((TAU * radius / zoom_level) as usize).max(8);
+                    markers.extend((0..steps).map(|i| {
+                        let angle = i as f32 / steps as f32 * TAU;
+                        let offs = Vec2::new(angle.cos(), angle.sin()) * radius;
+                        (centre + offs, '*', fg)
+                    }));
+                    markers.push((centre, '#', color::Yellow.fg_str()));
+                }
+                Kind::Beam => markers.push((centre, '~', color::LightMagenta.fg_str())),
+            }
+        }
+        markers
+    }
+}
@@ -0,0 +1,121 @@
+use termion::{color, style};
+use veloren_client::{Client, EcsEntity, Join, WorldExt};
+use veloren_common::{
+    comp::{self, Alignment, Body},
+    uid::Uid,
+};
+use veloren_common_net::sync::WorldSyncExt;
+
+/// A short human-readable name for a body, e.g. `Wolf` or `Human`.
+pub fn body_name(body: &Body) -> String {
+    match body {
+        Body::Humanoid(body) => format!("{:?}", body.species),
+        Body::QuadrupedLow(body) => format!("{:?}", body.species),
+        Body::QuadrupedSmall(body) => format!("{:?}", body.species),
+        Body::QuadrupedMedium(body) => format!("{:?}", body.species),
+        Body::BirdMedium(body) => format!("{:?}", body.species),
+        Body::BirdLarge(body) => format!("{:?}", body.species),
+        Body::FishSmall(body) => format!("{:?}", body.species),
+        Body::FishMedium(body) => format!("{:?}", body.species),
+        Body::BipedLarge(body) => format!("{:?}", body.species),
+        Body::BipedSmall(body) => format!("{:?}", body.species),
+        Body::Golem(body) => format!("{:?}", body.species),
+        Body::Dragon(body) => format!("{:?}", body.species),
+        Body::Theropod(body) => format!("{:?}", body.species),
+        Body::Arthropod(body) => format!("{:?}", body.species),
+        Body::Crustacean(body) => format!("{:?}", body.species),
+        Body::Object(body) => format!("{:?}", body),
+        Body::Ship(body) => format!("{:?}", body),
+        Body::ItemDrop(_) => "Item".to_string(),
+    }
+}
+
+/// The name to show for an entity: the alias for players, the NPC name otherwise, falling back
+/// to the body name.
+pub fn entity_name(client: &Client, entity: EcsEntity) -> String {
+    let state = client.state();
+    if let Some(player) = state.read_storage::<comp::Player>().get(entity) {
+        player.alias.clone()
+    } else if let Some(stats) = state.read_storage::<comp::Stats>().get(entity) {
+        stats.name.clone()
+    } else if let Some(body) = state.read_storage::<Body>().get(entity) {
+        body_name(body)
+    } else {
+        "Unknown".to_string()
+    }
+}
+
+/// Find a loaded player entity by their alias.
+pub fn find_player(client: &Client, name: &str) -> Option<(Uid, String)> {
+    let state = client.state();
+    let players = state.ecs().read_storage::<comp::Player>();
+    let uids = state.ecs().read_storage::<Uid>();
+
+    (&players, &uids)
+        .join()
+        .find(|(player, _)| player.alias.eq_ignore_ascii_case(name))
+        .map(|(player, uid)| (*uid, player.alias.clone()))
+}
+
+/// The name for an entity we only know the uid of, which might not be loaded nearby.
+pub fn uid_name(client: &Client, uid: Uid) -> String {
+    match client.state().ecs().entity_from_uid(uid) {
+        Some(entity) => entity_name(client, entity),
+        None => client
+            .player_list()
+            .get(&uid)
+            .map_or("Unknown".to_string(), |info| info.player_alias.clone()),
+    }
+}
+
+/// How an entity stands towards the player.
+#[derive(Copy, Clone, PartialEq)]
+pub enum Disposition {
+    Me,
+    Friendly,
+    Neutral,
+    Hostile,
+}
+
+impl Disposition {
+    /// The colour to draw an entity with this disposition in.
+    pub fn color(self) -> String {
+        match self {
+            Disposition::Me => format!("{}{}", style::Bold, color::Cyan.fg_str()),
+            Disposition::Friendly => color::Green.fg_str().to_string(),
+            Disposition::Neutral => color::Yellow.fg_str().to_string(),
+            Disposition::Hostile => color::Red.fg_str().to_string(),
+        }
+    }
+}
+
+/// Whether an entity is us, in our group or owned by us, out to get us, or neither. Returns
+/// `None` for things without an alignment, like items and objects.
+pub fn disposition(client: &Client, entity: EcsEntity) -> Option<Disposition> {
+    if entity == client.entity() {
+        return Some(Disposition::Me);
+    }
+    let state = client.state();
+    let alignments = state.read_storage::<Alignment>();
+    let groups = state.read_storage::<comp::Group>();
+    let alignment = *alignments.get(entity)?;
+    let player_uid = *state.read_storage::<Uid>().get(client.entity())?;
+    let player_alignment = alignments
+        .get(client.entity())
+        .copied()
+        .unwrap_or(Alignment::Owned(player_uid));
+
+    let same_group = groups
+        .get(entity)
+        .zip(groups.get(client.entity()))
+        .map_or(false, |(a, b)| a == b);
+    Some(if same_group || alignment == Alignment::Owned(player_uid) {
+        Disposition::Friendly
+    } else if alignment.hostile_towards(player_alignment) {
+        Disposition::Hostile
+    } else if matches!(alignment, Alignment::Npc | Alignment::Tame) {
+        Disposition::Friendly
+    } else {
+        Disposition::Neutral
+    })
+}
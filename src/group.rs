@@ -0,0 +1,257 @@
+use crate::{
+    display::{bar, Display},
+    entity::{find_player, uid_name},
+    inventory::PANEL_SIZE,
+};
+use termion::color;
+use vek::*;
+use veloren_client::{Client, Join, WorldExt};
+use veloren_common::{
+    comp::{self, group::Role, invite::InviteKind},
+    uid::Uid,
+};
+use veloren_common_net::sync::WorldSyncExt;
+
+/// How far away a player can be for the invite key to pick them.
+const INVITE_RANGE: f32 = 20.0;
+const LIST_ROWS: usize = 18;
+
+/// A group management action, from the group panel or a chat command.
+pub enum GroupAction {
+    Invite(Uid, String),
+    Kick(Uid, String),
+    Leader(Uid, String),
+    Leave,
+}
+
+impl GroupAction {
+    /// Parse a locally handled group command, or return `None` if `argv` isn't one.
+    pub fn from_command(client: &Client, argv: &str) -> Option<Result<Self, String>> {
+        let mut args = argv.split_whitespace();
+        let cmd = args.next()?;
+        let make: fn(Uid, String) -> Self = match cmd {
+            "/ginvite" => GroupAction::Invite,
+            "/gkick" => GroupAction::Kick,
+            "/glead" => GroupAction::Leader,
+            "/gleave" => return Some(Ok(GroupAction::Leave)),
+            _ => return None,
+        };
+        Some(match args.next() {
+            Some(name) => find_player(client, name)
+                .map(|(uid, alias)| make(uid, alias))
+                .ok_or_else(|| format!("No player named '{}' nearby", name)),
+            None => Err(format!("Usage: {} <player>", cmd)),
+        })
+    }
+
+    /// Send the action to the server, returning a description of what happened.
+    pub fn apply(self, client: &mut Client) -> String {
+        match self {
+            GroupAction::Invite(uid, name) => {
+                client.send_invite(uid, InviteKind::Group);
+                format!("Invited {} to the group", name)
+            }
+            GroupAction::Kick(uid, name) => {
+                client.kick_from_group(uid);
+                format!("Kicked {} from the group", name)
+            }
+            GroupAction::Leader(uid, name) => {
+                client.assign_group_leader(uid);
+                format!("Made {} the group leader", name)
+            }
+            GroupAction::Leave => {
+                client.leave_group();
+                "Left the group".to_string()
+            }
+        }
+    }
+}
+
+struct Member {
+    uid: Uid,
+    name: String,
+    role: Role,
+    health: Option<(f32, f32)>,
+}
+
+/// Everyone in our group, including ourselves, with the leader first.
+fn members(client: &Client) -> Vec<Member> {
+    let state = client.state();
+    let player_uid = state.read_storage::<Uid>().get(client.entity()).copied();
+    let leader = client.group_leader();
+    let healths = state.read_storage::<comp::Health>();
+
+    let mut members = client
+        .group_members()
+        .iter()
+        .map(|(uid, role)| (*uid, *role))
+        .chain(player_uid.map(|uid| (uid, Role::Member)))
+        .map(|(uid, role)| {
+            let entity = state.ecs().entity_from_uid(uid);
+            let name = uid_name(client, uid);
+            let health = entity
+                .and_then(|e| healths.get(e))
+                .map(|health| (health.current(), health.maximum()));
+            Member {
+                uid,
+                name,
+                role,
+                health,
+            }
+        })
+        .collect::<Vec<_>>();
+    members.sort_by_key(|member| (Some(member.uid) != leader, member.name.clone()));
+    members.dedup_by_key(|member| member.uid);
+    members
+}
+
+/// Cursor state for the group screen.
+#[derive(Default)]
+pub struct GroupPanel {
+    cursor: usize,
+    status: Option<String>,
+}
+
+impl GroupPanel {
+    pub fn move_cursor(&mut self, dy: i32) {
+        self.cursor = (self.cursor as i32 + dy).max(0) as usize;
+    }
+
+    pub fn set_status(&mut self, status: String) {
+        self.status = Some(status);
+    }
+
+    /// Work out the group action for a key press in the panel. Invites go to `target` if it's a
+    /// player, otherwise to the nearest player.
+    pub fn action(
+        &mut self,
+        client: &Client,
+        key: char,
+        target: Option<Uid>,
+    ) -> Option<GroupAction> {
+        let selected = members(client).into_iter().nth(self.cursor);
+        let player_uid = client
+            .state()
+            .read_storage::<Uid>()
+            .get(client.entity())
+            .copied();
+        let is_leader = client.group_leader().is_some() && client.group_leader() == player_uid;
+        let action = match key {
+            'I' => match target_player(client, target).or_else(|| nearest_player(client)) {
+                Some((uid, name)) => Some(GroupAction::Invite(uid, name)),
+                None => {
+                    self.status = Some("No player nearby to invite".to_string());
+                    return None;
+                }
+            },
+            'X' if client.group_leader().is_some() => Some(GroupAction::Leave),
+            'K' | 'L' if !is_leader => {
+                self.status = Some("Only the group leader can do that".to_string());
+                return None;
+            }
+            'K' | 'L' => match selected {
+                Some(member) if Some(member.uid) != player_uid => Some(match key {
+                    'K' => GroupAction::Kick(member.uid, member.name),
+                    _ => GroupAction::Leader(member.uid, member.name),
+                }),
+                _ => None,
+            },
+            _ => None,
+        };
+        if action.is_none() {
+            self.status = Some("Nothing to do".to_string());
+        }
+        action
+    }
+
+    pub fn draw(&mut self, display: &mut Display, pos: Vec2<u16>, client: &Client) {
+        let inner = PANEL_SIZE.x - 4;
+        let members = members(client);
+        let leader = client.group_leader();
+        self.cursor = self.cursor.min(members.len().saturating_sub(1));
+
+        let title = if leader.is_some() {
+            format!("Group ({} members)", members.len())
+        } else {
+            "Group (not in a group)".to_string()
+        };
+        display.draw_box(pos, PANEL_SIZE, &title);
+
+        if leader.is_some() {
+            for (i, member) in members.iter().enumerate().take(LIST_ROWS) {
+                let marker = match member.role {
+                    _ if Some(member.uid) == leader => '*',
+                    Role::Pet => 'p',
+                    Role::Member => ' ',
+                };
+                let health = member
+                    .health
+                    .map_or("  (far away)".to_string(), |(cur, max)| {
+                        format!("{} {:.0}", bar(cur / max.max(1.0), 12), cur)
+                    });
+                let name = member.name.chars().take(14).collect::<String>();
+                let line = format!("{}{:<14} {}", marker, name, health);
+                let fg = match member.health {
+                    Some((cur, max)) if cur < max * 0.3 => color::Red.fg_str(),
+                    Some(_) => color::Green.fg_str(),
+                    None => color::LightBlack.fg_str(),
+                };
+                let fg = if i == self.cursor {
+                    format!("{}{}", color::Bg(color::Blue), fg)
+                } else {
+                    fg.to_string()
+                };
+                display.text((pos.x + 2, pos.y + 1 + i as u16), inner, &fg, &line);
+            }
+        } else {
+            let fg = color::LightBlack.fg_str();
+            display.text(
+                (pos.x + 2, pos.y + 1),
+                inner,
+                fg,
+                "Press I to invite target or nearest",
+            );
+        }
+
+        if let Some(status) = &self.status {
+            let y = pos.y + PANEL_SIZE.y - 3;
+            display.text((pos.x + 2, y), inner, color::Green.fg_str(), status);
+        }
+        display.text(
+            (pos.x + 2, pos.y + PANEL_SIZE.y - 2),
+            inner,
+            color::LightBlack.fg_str(),
+            "I invite K kick L leader X leave",
+        );
+    }
+}
+
+/// The selected target, if it's another player.
+fn target_player(client: &Client, target: Option<Uid>) -> Option<(Uid, String)> {
+    let uid = target?;
+    let state = client.state();
+    let entity = state.ecs().entity_from_uid(uid)?;
+    if entity == client.entity() {
+        return None;
+    }
+    let players = state.read_storage::<comp::Player>();
+    Some((uid, players.get(entity)?.alias.clone()))
+}
+
+/// The closest other player in inviting range.
+fn nearest_player(client: &Client) -> Option<(Uid, String)> {
+    let state = client.state();
+    let player_pos = state.read_storage::<comp::Pos>().get(client.entity())?.0;
+    let entities = state.ecs().entities();
+    let positions = state.ecs().read_storage::<comp::Pos>();
+    let players = state.ecs().read_storage::<comp::Player>();
+    let uids = state.ecs().read_storage::<Uid>();
+
+    (&entities, &positions, &players, &uids)
+        .join()
+        .filter(|(entity, ..)| *entity != client.entity())
+        .map(|(_, pos, player, uid)| (pos.0.distance(player_pos), *uid, player.alias.clone()))
+        .filter(|(dist, ..)| *dist < INVITE_RANGE)
+        .min_by(|(a, ..), (b, ..)| a.total_cmp(b))
+        .map(|(_, uid, alias)| (uid, alias))
+}
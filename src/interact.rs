@@ -0,0 +1,242 @@
+use crate::entity::entity_name;
+use vek::*;
+use veloren_client::{Client, EcsEntity, Join, WorldExt};
+use veloren_common::{
+    comp::{self, dialogue::Subject, invite::InviteKind},
+    consts::{MAX_MOUNT_RANGE, MAX_PICKUP_RANGE},
+    link::Is,
+    mounting::Rider,
+    terrain::SpriteKind,
+    uid::Uid,
+    vol::ReadVol,
+};
+use veloren_common_net::sync::WorldSyncExt;
+
+/// Something near the player that can be picked up or collected.
+pub enum Interactable {
+    Item {
+        entity: EcsEntity,
+        name: String,
+    },
+    Sprite {
+        pos: Vec3<i32>,
+        kind: SpriteKind,
+        container: bool,
+    },
+}
+
+impl Interactable {
+    /// Find the closest thing in pickup range of the player.
+    pub fn nearest(client: &Client) -> Option<Self> {
+        let state = client.state();
+        let player_pos = state.read_storage::<comp::Pos>().get(client.entity())?.0;
+
+        let entities = state.ecs().entities();
+        let positions = state.ecs().read_storage::<comp::Pos>();
+        let items = state.ecs().read_storage::<comp::PickupItem>();
+        let nearest_item = (&entities, &positions, &items)
+            .join()
+            .map(|(entity, pos, item)| {
+                let item = Interactable::Item {
+                    entity,
+                    name: item.item().name().to_string(),
+                };
+                (item, pos.0.distance_squared(player_pos))
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b));
+
+        let terrain = state.terrain();
+        let centre = player_pos.map(|e| e.floor() as i32);
+        let range = MAX_PICKUP_RANGE.ceil() as i32;
+        let nearest_sprite = (-range..=range)
+            .flat_map(|x| {
+                (-range..=range)
+                    .flat_map(move |y| (-range..=range).map(move |z| Vec3::new(x, y, z)))
+            })
+            .map(|offs| centre + offs)
+            .filter_map(|pos| {
+                let block = terrain.get(pos).ok()?;
+                let kind = block.get_sprite().filter(|_| block.is_collectible())?;
+                let sprite = Interactable::Sprite {
+                    pos,
+                    kind,
+                    container: kind.is_container(),
+                };
+                Some((
+                    sprite,
+                    (pos.map(|e| e as f32) + 0.5).distance_squared(player_pos),
+                ))
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b));
+
+        nearest_item
+            .into_iter()
+            .chain(nearest_sprite)
+            .filter(|(_, dist_sqrd)| *dist_sqrd < MAX_PICKUP_RANGE.powi(2))
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(interactable, _)| interactable)
+    }
+
+    pub fn prompt(&self) -> String {
+        match self {
+            Interactable::Item { name, .. } => format!("Pick up {}", name),
+            Interactable::Sprite {
+                kind,
+                container: true,
+                ..
+            } => format!("Open {:?}", kind),
+            Interactable::Sprite { kind, .. } => format!("Collect {:?}", kind),
+        }
+    }
+
+    pub fn interact(&self, client: &mut Client) {
+        match self {
+            Interactable::Item { entity, .. } => client.pick_up(*entity),
+            Interactable::Sprite { pos, .. } => client.collect_block(*pos),
+        }
+    }
+}
+
+/// Something we can do with a creature or NPC.
+#[derive(Copy, Clone)]
+pub enum Action {
+    Mount,
+    Dismount,
+    Pet,
+    Talk,
+    Trade,
+}
+
+impl Action {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::Mount => "Mount",
+            Action::Dismount => "Dismount",
+            Action::Pet => "Pet",
+            Action::Talk => "Talk",
+            Action::Trade => "Trade",
+        }
+    }
+}
+
+/// The list of interactions available with a particular entity.
+pub struct InteractMenu {
+    entity: EcsEntity,
+    name: String,
+    actions: Vec<Action>,
+}
+
+impl InteractMenu {
+    /// Build the menu for `entity`, or `None` if there's nothing we can do with it.
+    pub fn new(client: &Client, entity: EcsEntity) -> Option<Self> {
+        let state = client.state();
+        let player_uid = *state.read_storage::<Uid>().get(client.entity())?;
+        let player_pos = state.read_storage::<comp::Pos>().get(client.entity())?.0;
+        let pos = state.read_storage::<comp::Pos>().get(entity)?.0;
+        let body = *state.read_storage::<comp::Body>().get(entity)?;
+        let alignment = state.read_storage::<comp::Alignment>().get(entity).copied();
+        let uid = state.read_storage::<Uid>().get(entity).copied();
+        let riding = state
+            .read_storage::<Is<Rider>>()
+            .get(client.entity())
+            .map(|is_rider| is_rider.mount);
+
+        let mut actions = Vec::new();
+        if riding.is_some() && riding == uid {
+            actions.push(Action::Dismount);
+        } else if pos.distance_squared(player_pos) < MAX_MOUNT_RANGE.powi(2) {
+            let owned = alignment == Some(comp::Alignment::Owned(player_uid));
+            if riding.is_none() && (owned || matches!(body, comp::Body::Ship(_))) {
+                actions.push(Action::Mount);
+            }
+            let is_player = state.read_storage::<comp::Player>().get(entity).is_some();
+            match (body, alignment) {
+                (comp::Body::Humanoid(_), _) if is_player => actions.push(Action::Trade),
+                (comp::Body::Humanoid(_), Some(comp::Alignment::Npc)) => {
+                    actions.push(Action::Talk);
+                    actions.push(Action::Trade);
+                }
+                (comp::Body::Humanoid(_) | comp::Body::Object(_) | comp::Body::ItemDrop(_), _) => {}
+                (comp::Body::Ship(_), _) => {}
+                (_, Some(comp::Alignment::Enemy)) => {}
+                _ => actions.push(Action::Pet),
+            }
+        }
+
+        (!actions.is_empty()).then(|| Self {
+            entity,
+            name: entity_name(client, entity),
+            actions,
+        })
+    }
+
+    /// Find the nearest entity with something to do. If we're riding something, that's the mount.
+    pub fn nearest(client: &Client) -> Option<Self> {
+        let state = client.state();
+        let mount = state
+            .read_storage::<Is<Rider>>()
+            .get(client.entity())
+            .and_then(|is_rider| state.ecs().entity_from_uid(is_rider.mount));
+        if let Some(mount) = mount {
+            return Self::new(client, mount);
+        }
+
+        let player_pos = state.read_storage::<comp::Pos>().get(client.entity())?.0;
+        let entities = state.ecs().entities();
+        let positions = state.ecs().read_storage::<comp::Pos>();
+        let mut nearby = (&entities, &positions)
+            .join()
+            .filter(|(entity, _)| *entity != client.entity())
+            .map(|(entity, pos)| (entity, pos.0.distance_squared(player_pos)))
+            .filter(|(_, dist_sqrd)| *dist_sqrd < MAX_MOUNT_RANGE.powi(2))
+            .collect::<Vec<_>>();
+        nearby.sort_by(|(_, a), (_, b)| a.total_cmp(b));
+
+        nearby
+            .into_iter()
+            .find_map(|(entity, _)| Self::new(client, entity))
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn actions(&self) -> &[Action] {
+        &self.actions
+    }
+
+    /// Perform the `idx`th action in the menu, returning whether it existed.
+    pub fn choose(&self, client: &mut Client, idx: usize) -> bool {
+        let Some(action) = self.actions.get(idx) else {
+            return false;
+        };
+        match action {
+            Action::Mount => client.mount(self.entity),
+            Action::Dismount => client.unmount(),
+            Action::Pet => client.do_pet(self.entity),
+            Action::Talk => client.npc_interact(self.entity, Subject::Regular),
+            Action::Trade => {
+                let uid = client
+                    .state()
+                    .read_storage::<Uid>()
+                    .get(self.entity)
+                    .copied();
+                if let Some(uid) = uid {
+                    client.send_invite(uid, InviteKind::Trade);
+                }
+            }
+        }
+        true
+    }
+}
+
+/// The name of whatever the player is currently riding.
+pub fn mount_name(client: &Client) -> Option<String> {
+    let state = client.state();
+    let mount = state
+        .read_storage::<Is<Rider>>()
+        .get(client.entity())?
+        .mount;
+    let entity = state.ecs().entity_from_uid(mount)?;
+    Some(entity_name(client, entity))
+}
@@ -0,0 +1,511 @@
+use crate::display::{wrap_text, Display};
+use termion::{color, event::Key};
+use vek::*;
+use veloren_client::Client;
+use veloren_common::comp::{
+    inventory::{
+        slot::{InvSlotId, Slot},
+        InventorySortOrder,
+    },
+    item::{armor::Protection, Item, ItemKind, MaterialStatManifest, Quality},
+    Inventory,
+};
+
+const COLUMNS: usize = 2;
+const CELL_WIDTH: u16 = 18;
+const GRID_ROWS: usize = 12;
+/// The most split operations we'll issue while trying to split off an exact amount.
+const MAX_SPLIT_OPS: u32 = 12;
+/// Ticks to wait for the server to act on a split before giving up.
+const SPLIT_TIMEOUT_TICKS: u32 = 120;
+const SORT_ORDERS: [InventorySortOrder; 4] = [
+    InventorySortOrder::Name,
+    InventorySortOrder::Quality,
+    InventorySortOrder::Category,
+    InventorySortOrder::Amount,
+];
+
+pub const PANEL_SIZE: Vec2<u16> = Vec2::new(40, 25);
+
+/// The colour used to draw an item's name, based on its quality.
+pub fn quality_color(quality: Quality) -> String {
+    match quality {
+        Quality::Low => color::LightBlack.fg_str().to_string(),
+        Quality::Common => color::White.fg_str().to_string(),
+        Quality::Moderate => color::Green.fg_str().to_string(),
+        Quality::High => color::Blue.fg_str().to_string(),
+        Quality::Epic => color::Magenta.fg_str().to_string(),
+        Quality::Legendary => color::Yellow.fg_str().to_string(),
+        Quality::Artifact => color::Cyan.fg_str().to_string(),
+        Quality::Debug => color::Red.fg_str().to_string(),
+    }
+}
+
+fn format_protection(protection: Option<Protection>) -> String {
+    match protection {
+        Some(Protection::Normal(p)) => format!("{:.1}", p),
+        Some(Protection::Invincible) => "Invincible".to_string(),
+        None => "0".to_string(),
+    }
+}
+
+/// The item's category along with a few lines describing its stats.
+pub fn item_stats(item: &Item) -> (&'static str, Vec<String>) {
+    let durability = item.stats_durability_multiplier();
+    match &*item.kind() {
+        ItemKind::Tool(tool) => {
+            let stats = tool.stats(durability);
+            (
+                "Weapon",
+                vec![
+                    format!("Power {:.1}  Speed {:.2}", stats.power, stats.speed),
+                    format!(
+                        "Range {:.2}  Energy eff. {:.2}",
+                        stats.range, stats.energy_efficiency
+                    ),
+                ],
+            )
+        }
+        ItemKind::Armor(armor) => {
+            let stats = armor.stats(&MaterialStatManifest::load().read(), durability);
+            (
+                "Armour",
+                vec![
+                    format!(
+                        "Protection {}  Poise res. {}",
+                        format_protection(stats.protection),
+                        format_protection(stats.poise_resilience)
+                    ),
+                    format!("Max energy {:+.0}", stats.energy_max.unwrap_or(0.0)),
+                ],
+            )
+        }
+        ItemKind::Glider => ("Glider", Vec::new()),
+        ItemKind::Lantern(_) => ("Lantern", Vec::new()),
+        ItemKind::Consumable { .. } => ("Consumable", Vec::new()),
+        ItemKind::Utility { .. } => ("Utility", Vec::new()),
+        ItemKind::Ingredient { .. } => ("Ingredient", Vec::new()),
+        ItemKind::ModularComponent(_) => ("Component", Vec::new()),
+        _ => ("Item", Vec::new()),
+    }
+}
+
+/// A change to the player's inventory, requested through one of the inventory panels.
+pub enum InvAction {
+    Swap(Slot, Slot),
+    SplitSwap(Slot, Slot),
+    Use(Slot),
+    Drop(Slot),
+    SplitDrop(Slot),
+    Sort(InventorySortOrder),
+}
+
+impl InvAction {
+    pub fn apply(self, client: &mut Client) {
+        match self {
+            InvAction::Swap(a, b) => client.swap_slots(a, b),
+            InvAction::SplitSwap(a, b) => client.split_swap_slots(a, b),
+            InvAction::Use(slot) => client.use_slot(slot),
+            InvAction::Drop(slot) => client.drop_slot(slot),
+            InvAction::SplitDrop(slot) => client.split_drop_slot(slot),
+            InvAction::Sort(order) => client.sort_inventory(order),
+        }
+    }
+}
+
+/// What to do with the source slot once a destination has been picked.
+#[derive(Copy, Clone, PartialEq)]
+enum Pending {
+    Move,
+    Split,
+}
+
+/// An in-progress split of an exact amount. The server only splits stacks in half, so we get
+/// there by halving back and forth between the two slots, one step per server update.
+struct SplitJob {
+    from: InvSlotId,
+    to: InvSlotId,
+    target: u32,
+    last: Option<(u32, u32)>,
+    ops: u32,
+    waited: u32,
+}
+
+fn amount_in(inv: &Inventory, slot: InvSlotId) -> u32 {
+    inv.get(slot).map_or(0, |item| item.amount())
+}
+
+/// How many items the destination will end up with if we split towards `target` the way
+/// `InventoryPanel::tick` does, given that each split moves half a stack, rounded down.
+fn split_result(mut from: u32, mut to: u32, target: u32) -> u32 {
+    for _ in 0..MAX_SPLIT_OPS {
+        let moved = if to < target {
+            from / 2
+        } else if to > target {
+            to / 2
+        } else {
+            break;
+        };
+        if moved == 0 {
+            break;
+        }
+        if to < target {
+            from -= moved;
+            to += moved;
+        } else {
+            to -= moved;
+            from += moved;
+        }
+    }
+    to
+}
+
+/// Cursor and scroll state for the inventory screen, along with any slot that's been picked as
+/// the source of a move or split.
+#[derive(Default)]
+pub struct InventoryPanel {
+    cursor: usize,
+    scroll: usize,
+    source: Option<(InvSlotId, Pending)>,
+    status: Option<String>,
+    /// The source and destination of a split, along with the quantity typed so far.
+    prompt: Option<(InvSlotId, InvSlotId, String)>,
+    split_job: Option<SplitJob>,
+    sort_order: usize,
+}
+
+impl InventoryPanel {
+    /// Move the cursor around the slot grid, staying within the inventory.
+    pub fn move_cursor(&mut self, dx: i32, dy: i32, inv: &Inventory) {
+        let slots = inv.slots_with_id().count();
+        let idx = self.cursor as i32 + dx + dy * COLUMNS as i32;
+        self.cursor = idx.clamp(0, slots.saturating_sub(1) as i32) as usize;
+    }
+
+    /// The slot currently under the cursor.
+    pub fn selected(&self, inv: &Inventory) -> Option<InvSlotId> {
+        inv.slots_with_id()
+            .nth(self.cursor)
+            .map(|(slot_id, _)| slot_id)
+    }
+
+    fn begin(&mut self, inv: &Inventory, pending: Pending) -> Option<InvAction> {
+        let slot = self.selected(inv)?;
+        let Some(item) = inv.get(slot) else {
+            self.status = Some("That slot is empty".to_string());
+            return None;
+        };
+        let verb = match pending {
+            Pending::Move => "Moving",
+            Pending::Split => "Splitting",
+        };
+        self.status = Some(format!("{} {}, pick a destination", verb, item.name()));
+        self.source = Some((slot, pending));
+        None
+    }
+
+    fn finish(&mut self, inv: &Inventory, from: InvSlotId, pending: Pending) -> Option<InvAction> {
+        let to = self.selected(inv)?;
+        if from == to {
+            self.status = Some("Cancelled".to_string());
+            return None;
+        }
+        let item = inv.get(from)?;
+        let name = item.name().to_string();
+        if pending == Pending::Split {
+            if inv.get(to).map_or(false, |other| other.name() != name) {
+                self.status = Some("Split into an empty slot or a matching stack".to_string());
+            } else if item.amount() < 2 {
+                self.status = Some(format!("There's only one {}", name));
+            } else {
+                let max = item.amount() - 1;
+                self.status = Some(format!("Split how many? (1-{}, enter for half)", max));
+                self.prompt = Some((from, to, String::new()));
+            }
+            return None;
+        }
+        self.status = Some(match inv.get(to) {
+            Some(other) if other.name() != name => {
+                format!("Swapped {} with {}", name, other.name())
+            }
+            _ => format!("Moved {}", name),
+        });
+        Some(InvAction::Swap(Slot::Inventory(from), Slot::Inventory(to)))
+    }
+
+    pub fn is_prompting(&self) -> bool {
+        self.prompt.is_some()
+    }
+
+    /// Handle a key press while we're asking how many items to split off.
+    pub fn prompt_key(&mut self, key: Key, inv: &Inventory) {
+        let Some((from, to, input)) = self.prompt.as_mut() else {
+            return;
+        };
+        let (from, to) = (*from, *to);
+        match key {
+            Key::Char(c) if c.is_ascii_digit() => input.push(c),
+            Key::Backspace | Key::Char('\x08') => {
+                input.pop();
+            }
+            Key::Esc => {
+                self.prompt = None;
+                self.status = Some("Cancelled".to_string());
+            }
+            Key::Char('\n') => {
+                let total = amount_in(inv, from);
+                let target = if input.is_empty() {
+                    Ok(total / 2)
+                } else {
+                    input.parse::<u32>()
+                };
+                match target {
+                    Ok(target) if target > 0 && target < total => {
+                        self.prompt = None;
+                        self.status = Some(format!("Splitting off {}...", target));
+                        self.split_job = Some(SplitJob {
+                            from,
+                            to,
+                            target: target + amount_in(inv, to),
+                            last: None,
+                            ops: 0,
+                            waited: 0,
+                        });
+                    }
+                    _ => {
+                        input.clear();
+                        self.status = Some(format!("Enter a number from 1 to {}", total - 1));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Carry on with any split that's in progress, once the server has caught up with the last
+    /// step.
+    pub fn tick(&mut self, inv: &Inventory) -> Option<InvAction> {
+        let job = self.split_job.as_mut()?;
+        let now = (amount_in(inv, job.from), amount_in(inv, job.to));
+        if job.last == Some(now) {
+            job.waited += 1;
+            if job.waited > SPLIT_TIMEOUT_TICKS {
+                self.status = Some(format!("Split stopped with {} in the new stack", now.1));
+                self.split_job = None;
+            }
+            return None;
+        }
+        job.last = Some(now);
+        job.waited = 0;
+
+        if now.1 == job.target {
+            self.status = Some(format!("Split done, new stack has {}", now.1));
+            self.split_job = None;
+            None
+        } else if job.ops >= MAX_SPLIT_OPS {
+            self.status = Some(format!("Couldn't split exactly, new stack has {}", now.1));
+            self.split_job = None;
+            None
+        } else {
+            job.ops += 1;
+            let (from, to) = (Slot::Inventory(job.from), Slot::Inventory(job.to));
+            Some(if now.1 < job.target {
+                InvAction::SplitSwap(from, to)
+            } else {
+                InvAction::SplitSwap(to, from)
+            })
+        }
+    }
+
+    /// Drop half of the highlighted stack.
+    pub fn split_drop(&mut self, inv: &Inventory) -> Option<InvAction> {
+        self.source = None;
+        let slot = self.selected(inv)?;
+        let item = inv.get(slot)?;
+        self.status = Some(format!("Dropped {} {}", item.amount() / 2, item.name()));
+        Some(InvAction::SplitDrop(Slot::Inventory(slot)))
+    }
+
+    /// Sort the inventory, using the next sort order each time.
+    pub fn sort(&mut self) -> Option<InvAction> {
+        let order = SORT_ORDERS[self.sort_order];
+        self.sort_order = (self.sort_order + 1) % SORT_ORDERS.len();
+        self.status = Some(format!("Sorted by {:?}", order));
+        Some(InvAction::Sort(order))
+    }
+
+    fn begin_or_finish(&mut self, inv: &Inventory, pending: Pending) -> Option<InvAction> {
+        match self.source.take() {
+            Some((from, source_pending)) => self.finish(inv, from, source_pending),
+            None => self.begin(inv, pending),
+        }
+    }
+
+    /// Pick the highlighted slot as the item to move, or move the picked item to it.
+    pub fn select_or_move(&mut self, inv: &Inventory) -> Option<InvAction> {
+        self.begin_or_finish(inv, Pending::Move)
+    }
+
+    /// Pick the highlighted slot as the stack to split, or split the picked stack into it.
+    pub fn split(&mut self, inv: &Inventory) -> Option<InvAction> {
+        self.begin_or_finish(inv, Pending::Split)
+    }
+
+    pub fn use_item(&mut self, inv: &Inventory) -> Option<InvAction> {
+        self.source = None;
+        let slot = self.selected(inv)?;
+        let name = inv.get(slot)?.name().to_string();
+        self.status = Some(format!("Used {}", name));
+        Some(InvAction::Use(Slot::Inventory(slot)))
+    }
+
+    pub fn drop_item(&mut self, inv: &Inventory) -> Option<InvAction> {
+        self.source = None;
+        let slot = self.selected(inv)?;
+        let name = inv.get(slot)?.name().to_string();
+        self.status = Some(format!("Dropped {}", name));
+        Some(InvAction::Drop(Slot::Inventory(slot)))
+    }
+
+    /// Forget the picked source slot, returning whether there was one.
+    pub fn cancel(&mut self) -> bool {
+        let cancelled = self.source.take().is_some();
+        if cancelled {
+            self.status = Some("Cancelled".to_string());
+        }
+        cancelled
+    }
+
+    pub fn draw(&mut self, display: &mut Display, pos: Vec2<u16>, inv: &Inventory) {
+        let slots = inv.slots_with_id().collect::<Vec<_>>();
+        let rows = (slots.len() + COLUMNS - 1) / COLUMNS;
+        self.cursor = self.cursor.min(slots.len().saturating_sub(1));
+
+        // Keep the cursor's row on screen
+        let cursor_row = self.cursor / COLUMNS;
+        if cursor_row < self.scroll {
+            self.scroll = cursor_row;
+        } else if cursor_row >= self.scroll + GRID_ROWS {
+            self.scroll = cursor_row + 1 - GRID_ROWS;
+        }
+
+        let title = format!(
+            "Inventory {}/{} (rows {}-{} of {})",
+            inv.populated_slots(),
+            slots.len(),
+            self.scroll + 1,
+            (self.scroll + GRID_ROWS).min(rows),
+            rows
+        );
+        display.draw_box(pos, PANEL_SIZE, &title);
+
+        for row in 0..GRID_ROWS {
+            for col in 0..COLUMNS {
+                let idx = (self.scroll + row) * COLUMNS + col;
+                let Some((slot_id, item)) = slots.get(idx) else {
+                    continue;
+                };
+                let cell_pos = pos + Vec2::new(2 + col as u16 * (CELL_WIDTH + 1), 1 + row as u16);
+                let (fg, text) = match item {
+                    Some(item) => {
+                        let name = item.name();
+                        let amount = if item.amount() > 1 {
+                            format!("x{}", item.amount())
+                        } else {
+                            String::new()
+                        };
+                        let name_width = CELL_WIDTH as usize - amount.len() - 1;
+                        let name = name.chars().take(name_width).collect::<String>();
+                        let text = format!("{:<w$} {}", name, amount, w = name_width);
+                        (quality_color(item.quality()), text)
+                    }
+                    None => (
+                        color::LightBlack.fg_str().to_string(),
+                        "(empty)".to_string(),
+                    ),
+                };
+                let is_source = self.source.map_or(false, |(src, _)| src == *slot_id);
+                let fg = if idx == self.cursor {
+                    format!("{}{}", color::Bg(color::Blue), fg)
+                } else if is_source {
+                    format!("{}{}", color::Bg(color::Yellow), color::Black.fg_str())
+                } else {
+                    fg
+                };
+                display.text(cell_pos, CELL_WIDTH, &fg, &text);
+            }
+        }
+
+        // Details of the highlighted item
+        let inner = PANEL_SIZE.x - 4;
+        let details_y = pos.y + 2 + GRID_ROWS as u16;
+        let mut lines = Vec::new();
+        match slots.get(self.cursor) {
+            Some((_, Some(item))) => {
+                let (kind, stats) = item_stats(item);
+                lines.push((quality_color(item.quality()), item.name().to_string()));
+                lines.push((
+                    color::LightBlack.fg_str().to_string(),
+                    format!(
+                        "{} | {:?} | {}/{}",
+                        kind,
+                        item.quality(),
+                        item.amount(),
+                        item.max_amount()
+                    ),
+                ));
+                for line in wrap_text(&item.description(), inner as usize)
+                    .into_iter()
+                    .take(4)
+                {
+                    lines.push((color::Reset.fg_str().to_string(), line));
+                }
+                for line in stats {
+                    lines.push((color::Cyan.fg_str().to_string(), line));
+                }
+            }
+            _ => lines.push((
+                color::LightBlack.fg_str().to_string(),
+                "Empty slot".to_string(),
+            )),
+        }
+        for (i, (fg, line)) in lines.iter().take(6).enumerate() {
+            display.text((pos.x + 2, details_y + i as u16), inner, fg, line);
+        }
+        if let Some((from, to, input)) = &self.prompt {
+            let y = pos.y + PANEL_SIZE.y - 4;
+            let (from, to) = (amount_in(inv, *from), amount_in(inv, *to));
+            // Halving can't reach every amount, so say what we'd really end up with
+            let line = match input.parse::<u32>() {
+                Ok(amount) if amount > 0 && amount < from => {
+                    let reached = split_result(from, to, to + amount).saturating_sub(to);
+                    if reached == amount {
+                        format!("Amount: {}_", input)
+                    } else {
+                        format!("Amount: {}_ (can only get {})", input, reached)
+                    }
+                }
+                _ => format!("Amount: {}_", input),
+            };
+            display.text((pos.x + 2, y), inner, color::Yellow.fg_str(), &line);
+        }
+        if let Some(status) = &self.status {
+            let y = pos.y + PANEL_SIZE.y - 3;
+            display.text((pos.x + 2, y), inner, color::Green.fg_str(), status);
+        }
+        let hint = if self.prompt.is_some() {
+            "digits amount  enter split  esc cancel"
+        } else if self.source.is_some() {
+            "arrows move  m/v place  esc cancel"
+        } else {
+            "m move v split e use del/D drop o sort"
+        };
+        // The full list of keys only just fits, so use the margins inside the border too
+        display.text(
+            (pos.x + 1, pos.y + PANEL_SIZE.y - 2),
+            PANEL_SIZE.x - 2,
+            color::LightBlack.fg_str(),
+            hint,
+        );
+    }
+}
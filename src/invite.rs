@@ -0,0 +1,113 @@
+use crate::entity::uid_name;
+use std::time::{Duration, Instant};
+use termion::color;
+use veloren_client::Client;
+use veloren_common::{comp::invite::InviteKind, uid::Uid};
+
+/// An invite we've been sent, as reported by `Client::invite`.
+struct Invite {
+    inviter: Uid,
+    received: Instant,
+    timeout: Duration,
+    kind: InviteKind,
+}
+
+impl Invite {
+    fn remaining(&self) -> Duration {
+        self.timeout.saturating_sub(self.received.elapsed())
+    }
+}
+
+/// The invite waiting for an answer. The client only keeps the latest invite, so one that
+/// arrives while another is being shown replaces it.
+#[derive(Default)]
+pub struct PendingInvite {
+    invite: Option<Invite>,
+}
+
+impl PendingInvite {
+    /// Follow the client's invite, returning a message for the chat log if a new one replaced
+    /// the invite we were showing.
+    pub fn update(&mut self, client: &Client) -> Option<String> {
+        let Some((inviter, received, timeout, kind)) = client.invite() else {
+            self.invite = None;
+            return None;
+        };
+        if self
+            .invite
+            .as_ref()
+            .map_or(false, |invite| invite.received == received)
+        {
+            return None;
+        }
+        let replaced = self.invite.replace(Invite {
+            inviter,
+            received,
+            timeout,
+            kind,
+        });
+        replaced.map(|old| {
+            format!(
+                "The {} from {} was replaced by a newer invite",
+                kind_name(old.kind),
+                uid_name(client, old.inviter)
+            )
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.invite
+            .as_ref()
+            .map_or(true, |invite| invite.remaining().is_zero())
+    }
+
+    /// Accept or decline the invite, returning a message for the chat log.
+    pub fn answer(&mut self, client: &mut Client, accept: bool) -> Option<String> {
+        let invite = self.invite.take()?;
+        let name = uid_name(client, invite.inviter);
+        Some(if accept {
+            client.accept_invite();
+            format!("Accepted the {} from {}", kind_name(invite.kind), name)
+        } else {
+            client.decline_invite();
+            format!("Declined the {} from {}", kind_name(invite.kind), name)
+        })
+    }
+
+    /// The notification line for the invite, with its colour, until it times out.
+    pub fn prompt(&self, client: &Client) -> Option<(&'static str, String)> {
+        let invite = self.invite.as_ref()?;
+        if invite.remaining().is_zero() {
+            return None;
+        }
+        let remaining = invite.remaining().as_secs();
+        let fg = if remaining < 5 {
+            color::Red.fg_str()
+        } else {
+            color::Yellow.fg_str()
+        };
+        Some((
+            fg,
+            format!(
+                "{} from {} ({}s). Accept[U]/Decline[I]",
+                capitalize(kind_name(invite.kind)),
+                uid_name(client, invite.inviter),
+                remaining
+            ),
+        ))
+    }
+}
+
+fn kind_name(kind: InviteKind) -> &'static str {
+    match kind {
+        InviteKind::Group => "group invite",
+        InviteKind::Trade => "trade request",
+    }
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    chars
+        .next()
+        .map_or(String::new(), |c| c.to_uppercase().chain(chars).collect())
+}
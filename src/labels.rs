@@ -0,0 +1,92 @@
+use crate::{display::bar, entity::entity_name};
+use std::collections::HashSet;
+use termion::color;
+use vek::*;
+use veloren_client::{Client, EcsEntity};
+use veloren_common::comp;
+
+const MAX_NAME_LEN: usize = 10;
+
+/// A name and health label to draw next to an entity's glyph.
+pub struct Label {
+    pub pos: Vec2<u16>,
+    pub fg: &'static str,
+    pub text: String,
+}
+
+fn health_color(fraction: f32) -> &'static str {
+    if fraction > 0.66 {
+        color::Green.fg_str()
+    } else if fraction > 0.33 {
+        color::Yellow.fg_str()
+    } else {
+        color::Red.fg_str()
+    }
+}
+
+/// Labels for the creatures and players drawn at `glyphs`, nearest to the middle of the screen
+/// first. Each label goes beside, above or below its glyph, wherever it won't cover another glyph
+/// or label, and is left out if there's no room.
+pub fn labels(
+    client: &Client,
+    glyphs: &[(EcsEntity, Vec2<u16>)],
+    screen_size: Vec2<u16>,
+) -> Vec<Label> {
+    let state = client.state();
+    let stats = state.read_storage::<comp::Stats>();
+    let healths = state.read_storage::<comp::Health>();
+    let mut occupied = glyphs.iter().map(|(_, pos)| *pos).collect::<HashSet<_>>();
+    let centre = screen_size / 2;
+
+    let mut glyphs = glyphs
+        .iter()
+        .filter(|(entity, _)| *entity != client.entity() && stats.contains(*entity))
+        .collect::<Vec<_>>();
+    glyphs.sort_by_key(|(_, pos)| {
+        let offs = pos.as_::<i32>() - centre.as_::<i32>();
+        offs.x * offs.x + offs.y * offs.y
+    });
+
+    let mut labels = Vec::new();
+    for (entity, pos) in glyphs {
+        let name = entity_name(client, *entity)
+            .chars()
+            .take(MAX_NAME_LEN)
+            .collect::<String>();
+        let (fg, text) = match healths.get(*entity) {
+            Some(health) => {
+                let fraction = health.current() / health.maximum().max(1.0);
+                (
+                    health_color(fraction),
+                    format!("{} {}", name, bar(fraction, 5)),
+                )
+            }
+            None => (color::White.fg_str(), name),
+        };
+        let len = text.chars().count() as i32;
+        let (x, y) = (pos.x as i32, pos.y as i32);
+        let spot = [
+            (x + 1, y),
+            (x - len / 2, y - 1),
+            (x - len / 2, y + 1),
+            (x - len, y),
+        ]
+        .into_iter()
+        .find(|(lx, ly)| {
+            *lx >= 0
+                && *ly >= 0
+                && lx + len <= screen_size.x as i32
+                && *ly < screen_size.y as i32
+                && (*lx..lx + len).all(|cx| !occupied.contains(&Vec2::new(cx as u16, *ly as u16)))
+        });
+        if let Some((lx, ly)) = spot {
+            occupied.extend((lx..lx + len).map(|cx| Vec2::new(cx as u16, ly as u16)));
+            labels.push(Label {
+                pos: Vec2::new(lx as u16, ly as u16),
+                fg,
+                text,
+            });
+        }
+    }
+    labels
+}
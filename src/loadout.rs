@@ -0,0 +1,225 @@
+use crate::{
+    display::Display,
+    inventory::{item_stats, quality_color, InvAction, PANEL_SIZE},
+};
+use termion::color;
+use vek::*;
+use veloren_common::comp::{
+    inventory::slot::{ArmorSlot, EquipSlot, InvSlotId, Slot},
+    item::{armor::Protection, ItemKind, MaterialStatManifest},
+    Inventory,
+};
+
+/// Every slot in the loadout, in the order they're listed.
+const EQUIP_SLOTS: [EquipSlot; 22] = [
+    EquipSlot::ActiveMainhand,
+    EquipSlot::ActiveOffhand,
+    EquipSlot::InactiveMainhand,
+    EquipSlot::InactiveOffhand,
+    EquipSlot::Armor(ArmorSlot::Head),
+    EquipSlot::Armor(ArmorSlot::Neck),
+    EquipSlot::Armor(ArmorSlot::Shoulders),
+    EquipSlot::Armor(ArmorSlot::Chest),
+    EquipSlot::Armor(ArmorSlot::Hands),
+    EquipSlot::Armor(ArmorSlot::Ring1),
+    EquipSlot::Armor(ArmorSlot::Ring2),
+    EquipSlot::Armor(ArmorSlot::Back),
+    EquipSlot::Armor(ArmorSlot::Belt),
+    EquipSlot::Armor(ArmorSlot::Legs),
+    EquipSlot::Armor(ArmorSlot::Feet),
+    EquipSlot::Armor(ArmorSlot::Tabard),
+    EquipSlot::Armor(ArmorSlot::Bag1),
+    EquipSlot::Armor(ArmorSlot::Bag2),
+    EquipSlot::Armor(ArmorSlot::Bag3),
+    EquipSlot::Armor(ArmorSlot::Bag4),
+    EquipSlot::Lantern,
+    EquipSlot::Glider,
+];
+
+/// How many rows of the slot list fit above the summary.
+const LIST_ROWS: usize = 15;
+
+/// How much protection it takes to halve incoming damage.
+const FIFTY_PERCENT_DR_THRESHOLD: f32 = 60.0;
+
+fn slot_name(slot: EquipSlot) -> String {
+    match slot {
+        EquipSlot::Armor(armor) => format!("{:?}", armor),
+        EquipSlot::ActiveMainhand => "Mainhand".to_string(),
+        EquipSlot::ActiveOffhand => "Offhand".to_string(),
+        EquipSlot::InactiveMainhand => "Alt mainhand".to_string(),
+        EquipSlot::InactiveOffhand => "Alt offhand".to_string(),
+        EquipSlot::Lantern => "Lantern".to_string(),
+        EquipSlot::Glider => "Glider".to_string(),
+    }
+}
+
+/// Cursor state for the equipment screen. While `picking` is set, we're choosing an inventory
+/// item to put into the highlighted equipment slot.
+#[derive(Default)]
+pub struct LoadoutPanel {
+    cursor: usize,
+    picking: Option<usize>,
+}
+
+impl LoadoutPanel {
+    fn selected_slot(&self) -> EquipSlot {
+        EQUIP_SLOTS[self.cursor]
+    }
+
+    /// Inventory items that could go in the highlighted equipment slot.
+    fn candidates(&self, inv: &Inventory) -> Vec<InvSlotId> {
+        let slot = self.selected_slot();
+        inv.slots_with_id()
+            .filter_map(|(slot_id, item)| Some((slot_id, item.as_ref()?)))
+            .filter(|(_, item)| slot.can_hold(&item.kind()))
+            .map(|(slot_id, _)| slot_id)
+            .collect()
+    }
+
+    pub fn move_cursor(&mut self, dy: i32, inv: &Inventory) {
+        match self.picking {
+            Some(pick) => {
+                let max = self.candidates(inv).len().saturating_sub(1) as i32;
+                self.picking = Some((pick as i32 + dy).clamp(0, max) as usize);
+            }
+            None => {
+                let max = EQUIP_SLOTS.len() as i32 - 1;
+                self.cursor = (self.cursor as i32 + dy).clamp(0, max) as usize;
+            }
+        }
+    }
+
+    /// Start picking an item to equip, or equip the picked item.
+    pub fn equip(&mut self, inv: &Inventory) -> Option<InvAction> {
+        match self.picking.take() {
+            Some(pick) => {
+                let from = *self.candidates(inv).get(pick)?;
+                Some(InvAction::Swap(
+                    Slot::Inventory(from),
+                    Slot::Equip(self.selected_slot()),
+                ))
+            }
+            None => {
+                self.picking = Some(0);
+                None
+            }
+        }
+    }
+
+    /// Put the highlighted item back in the first free inventory slot.
+    pub fn unequip(&mut self, inv: &Inventory) -> Option<InvAction> {
+        self.picking = None;
+        let slot = self.selected_slot();
+        inv.equipped(slot)?;
+        let (empty, _) = inv.slots_with_id().find(|(_, item)| item.is_none())?;
+        Some(InvAction::Swap(Slot::Equip(slot), Slot::Inventory(empty)))
+    }
+
+    /// Back out of picking an item, returning whether there was anything to back out of.
+    pub fn cancel(&mut self) -> bool {
+        self.picking.take().is_some()
+    }
+
+    pub fn draw(&mut self, display: &mut Display, pos: Vec2<u16>, inv: &Inventory) {
+        let inner = PANEL_SIZE.x - 4;
+        display.draw_box(pos, PANEL_SIZE, "Equipment");
+
+        let picking = self.picking.map(|pick| (pick, self.candidates(inv)));
+        let rows: Vec<(String, String, bool)> = match &picking {
+            Some((pick, candidates)) => candidates
+                .iter()
+                .filter_map(|slot_id| inv.get(*slot_id))
+                .enumerate()
+                .map(|(i, item)| {
+                    let text = format!("{} x{}", item.name(), item.amount());
+                    (quality_color(item.quality()), text, i == *pick)
+                })
+                .collect(),
+            None => EQUIP_SLOTS
+                .iter()
+                .enumerate()
+                .map(|(i, slot)| {
+                    let (fg, name) = match inv.equipped(*slot) {
+                        Some(item) => (quality_color(item.quality()), item.name().to_string()),
+                        None => (color::LightBlack.fg_str().to_string(), "-".to_string()),
+                    };
+                    let text = format!("{:<12} {}", slot_name(*slot), name);
+                    (fg, text, i == self.cursor)
+                })
+                .collect(),
+        };
+
+        if let Some((_, candidates)) = &picking {
+            let title = format!("Equip to {}:", slot_name(self.selected_slot()));
+            display.text(
+                (pos.x + 2, pos.y + 1),
+                inner,
+                color::Yellow.fg_str(),
+                &title,
+            );
+            if candidates.is_empty() {
+                let fg = color::LightBlack.fg_str();
+                display.text((pos.x + 2, pos.y + 2), inner, fg, "Nothing fits this slot");
+            }
+        }
+        let list_y = pos.y + if picking.is_some() { 2 } else { 1 };
+        let selected = rows
+            .iter()
+            .position(|(_, _, selected)| *selected)
+            .unwrap_or(0);
+        let scroll = selected.saturating_sub(LIST_ROWS - 1);
+        for (i, (fg, text, selected)) in rows.iter().skip(scroll).take(LIST_ROWS).enumerate() {
+            let fg = if *selected {
+                format!("{}{}", color::Bg(color::Blue), fg)
+            } else {
+                fg.clone()
+            };
+            display.text((pos.x + 2, list_y + i as u16), inner, &fg, text);
+        }
+
+        // Summary of what the current loadout gives us
+        let msm = MaterialStatManifest::load().read();
+        let protection: f32 = EQUIP_SLOTS
+            .iter()
+            .filter_map(|slot| inv.equipped(*slot))
+            .filter_map(|item| match &*item.kind() {
+                ItemKind::Armor(armor) => {
+                    match armor
+                        .stats(&msm, item.stats_durability_multiplier())
+                        .protection
+                    {
+                        Some(Protection::Normal(p)) => Some(p),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            })
+            .sum();
+        let reduction = protection / (FIFTY_PERCENT_DR_THRESHOLD + protection.abs());
+        let mut summary = vec![format!(
+            "Protection {:.1} ({:.0}% damage reduction)",
+            protection,
+            reduction * 100.0
+        )];
+        if let Some(weapon) = inv.equipped(EquipSlot::ActiveMainhand) {
+            summary.extend(item_stats(weapon).1);
+        }
+        for (i, line) in summary.iter().enumerate() {
+            let y = pos.y + PANEL_SIZE.y - 6 + i as u16;
+            display.text((pos.x + 2, y), inner, color::Cyan.fg_str(), line);
+        }
+
+        let hint = if picking.is_some() {
+            "up/down pick  e equip  esc back"
+        } else {
+            "up/down move  e equip  u unequip  l close"
+        };
+        display.text(
+            (pos.x + 2, pos.y + PANEL_SIZE.y - 2),
+            inner,
+            color::LightBlack.fg_str(),
+            hint,
+        );
+    }
+}
@@ -1,8 +1,49 @@
+mod buffs;
+mod combat;
+mod crafting;
+mod death;
 mod display;
-use crate::comp::{humanoid, Body};
+mod effects;
+mod entity;
+mod group;
+mod interact;
+mod inventory;
+mod invite;
+mod labels;
+mod loadout;
+mod motion;
+mod pathfind;
+mod players;
+mod skills;
+mod target;
+mod theme;
+mod trade;
+mod travel;
+use crate::buffs::status_effects;
+use crate::combat::CombatLog;
+use crate::comp::Body;
+use crate::crafting::CraftingPanel;
+use crate::death::Death;
 use crate::display::Display;
+use crate::effects::{projectile_color, projectile_glyph, Effects};
+use crate::entity::disposition;
+use crate::group::{GroupAction, GroupPanel};
+use crate::interact::{mount_name, InteractMenu, Interactable};
+use crate::inventory::{InventoryPanel, PANEL_SIZE};
+use crate::invite::PendingInvite;
+use crate::labels::labels;
+use crate::loadout::LoadoutPanel;
+use crate::motion::{facing_tick, Trails};
+use crate::pathfind::Route;
+use crate::players::PlayersPanel;
+use crate::skills::{unspent_points, SkillsPanel};
+use crate::target::Targeting;
+use crate::theme::{Style, Theme};
+use crate::trade::TradePanel;
+use crate::travel::{Travel, ARRIVE_DIST, FOLLOW_DIST};
 use clap::{Arg, Command};
 use std::{
+    collections::HashSet,
     io::{stdin, stdout, Write},
     sync::{mpsc, Arc},
     thread,
@@ -12,15 +53,14 @@ use termion::{
     color,
     event::{Event as TermEvent, Key, MouseEvent},
     input::TermRead,
+    style,
 };
 use tokio::runtime::Runtime;
 use vek::*;
 use veloren_client::{addr::ConnectionArgs, Client, Event, Join, WorldExt};
 use veloren_common::{
-    clock::Clock, comp, comp::inventory::slot::Slot, comp::InputKind, terrain::SpriteKind,
-    vol::ReadVol, ViewDistances,
+    clock::Clock, comp, comp::InputKind, terrain::SpriteKind, vol::ReadVol, ViewDistances,
 };
-use veloren_common_net::sync::WorldSyncExt;
 
 fn main() {
     let screen_size = Vec2::new(80, 25);
@@ -30,17 +70,19 @@ fn main() {
     };
     let tps = 60;
     let mut is_glide_active: bool = false;
-    let mut invpos = 1;
-    let mut arrowed1: Option<Slot> = None;
-    let mut arrowed2: Option<Slot> = None;
-    let mut arrowed: Option<Slot> = None;
-    let mut use_slotid: Option<Slot> = None;
-    let mut use_item: bool = false;
-    let mut inv_toggle: bool = false;
-    let mut arrowedpos = 0;
-    let mut is_jump_active: bool = false;
-    let mut is_secondary_active: bool = false;
-    let mut is_primary_active: bool = false;
+    let mut panel: Option<Panel> = None;
+    let mut inventory_panel = InventoryPanel::default();
+    let mut loadout_panel = LoadoutPanel::default();
+    let mut crafting_panel = CraftingPanel::default();
+    let mut trade_panel = TradePanel::default();
+    let mut group_panel = GroupPanel::default();
+    let mut players_panel = PlayersPanel::default();
+    let mut skills_panel = SkillsPanel::default();
+    let mut was_trading = false;
+    let mut active_inputs: HashSet<InputKind> = HashSet::new();
+    let mut tapped_inputs: Vec<InputKind> = Vec::new();
+    let mut move_z: f32 = 0.0;
+    let mut is_path_jumping: bool = false;
     let matches = Command::new("Teloren")
         .version("0.2")
         .author("Joshua Barretto <joshua.s.barretto@gmail.com>")
@@ -76,6 +118,12 @@ fn main() {
                 .help("Select the character to play")
                 .required(true),
         )
+        .arg(
+            Arg::new("theme")
+                .long("theme")
+                .value_name("THEME_FILE")
+                .help("Set the glyph and colour theme file, created if it doesn't exist"),
+        )
         .get_matches();
 
     // Find arguments
@@ -90,6 +138,10 @@ fn main() {
         .unwrap_or("teloren_user".into());
     let password: String = matches.get_one("password").cloned().unwrap_or_default();
     let character_name: String = matches.get_one("character").cloned().unwrap_or_default();
+    let (mut theme, theme_messages) = match matches.get_one::<String>("theme") {
+        Some(path) => Theme::load(path),
+        None => (Theme::builtin(), Vec::new()),
+    };
     // Parse server socket
 
     let server_spec = format!("{}:{}", server_addr, server_port);
@@ -125,7 +177,6 @@ fn main() {
         .expect("Failed to create client instance");
 
     println!("Server info: {:?}", client.server_info());
-    println!("Players: {:?}", client.player_list());
 
     // Request character
     let mut clock = Clock::new(Duration::from_secs_f64(1.0 / tps as f64));
@@ -163,42 +214,34 @@ fn main() {
 
     let mut display = Display::new(screen_size, stdout());
     let mut zoom_level = 1.0;
+    let mut show_labels = false;
+    let mut show_facing = false;
+    let mut show_trails = false;
+    let mut trails = Trails::default();
     let mut tgt_pos = None;
-    let mut chat_log = Vec::new();
+    let mut route: Option<Route> = None;
+    let mut travel: Option<Travel> = None;
+    let mut chat_log = theme_messages;
     let mut chat_input = String::new();
     let mut chat_input_enabled = false;
+    let mut interact_menu: Option<InteractMenu> = None;
+    let mut invite = PendingInvite::default();
+    let mut death = Death::default();
+    let mut targeting = Targeting::default();
+    let mut combat_log = CombatLog::default();
+    let mut effects = Effects::default();
+    let mut last_glyphs = Vec::new();
 
     'running: for tick in 0.. {
-        // Get Health and Energy
-        let (current_health, max_health) = client
-            .current::<comp::Health>()
-            .map_or((0.0, 0.0), |health| (health.current(), health.maximum()));
-        let (current_energy, max_energy) = client
-            .current::<comp::Energy>()
-            .map_or((0.0, 0.0), |energy| (energy.current(), energy.maximum()));
-
-        // Invite Logic
-        let (inviter_uid, invite_kind) =
-            if let Some((inviter_uid, _, _, invite_kind)) = client.invite() {
-                (Some(inviter_uid), Some(invite_kind))
-            } else {
-                (None, None)
-            };
+        if tick % 30 == 0 {
+            chat_log.extend(theme.reload_if_changed());
+        }
+        if let Some(msg) = invite.update(&client) {
+            chat_log.push(msg);
+        }
+        death.update(&client);
+        targeting.update(&client);
 
-        //Get entity username from UID
-        let inviter_username = if let Some(uid) = inviter_uid {
-            if let Some(entity) = client.state().ecs().entity_from_uid(uid) {
-                if let Some(player) = client.state().read_storage::<comp::Player>().get(entity) {
-                    player.alias.clone()
-                } else {
-                    "".to_string()
-                }
-            } else {
-                "".to_string()
-            }
-        } else {
-            "".to_string()
-        };
         //Get player pos
         let player_pos = client
             .state()
@@ -221,6 +264,11 @@ fn main() {
 
         let mut inputs = comp::ControllerInputs::default();
 
+        // Release anything that was only tapped last tick
+        for input in tapped_inputs.drain(..) {
+            client.handle_input(input, false, None, None);
+        }
+
         // Handle inputs
         for c in key_rx.try_iter() {
             match c.unwrap() {
@@ -228,7 +276,25 @@ fn main() {
                     '\n' => {
                         if chat_input.is_empty() {
                         } else {
-                            if chat_input.clone().starts_with('/') {
+                            if let Some(cmd) =
+                                Travel::from_command(&client, &chat_input, targeting.uid())
+                            {
+                                match cmd {
+                                    Ok(cmd) => {
+                                        chat_log.push(cmd.describe());
+                                        travel = Some(cmd);
+                                        tgt_pos = None;
+                                    }
+                                    Err(err) => chat_log.push(err),
+                                }
+                            } else if let Some(cmd) =
+                                GroupAction::from_command(&client, &chat_input)
+                            {
+                                match cmd {
+                                    Ok(cmd) => chat_log.push(cmd.apply(&mut client)),
+                                    Err(err) => chat_log.push(err),
+                                }
+                            } else if chat_input.clone().starts_with('/') {
                                 let argv = chat_input.clone();
                                 client.send_command(
                                     argv.split_whitespace().next().unwrap().to_owned(),
@@ -246,92 +312,391 @@ fn main() {
                     }
                     c => chat_input.push(c),
                 },
+                TermEvent::Key(k) if interact_menu.is_some() => match k {
+                    Key::Char(c @ '1'..='9') => {
+                        let idx = c as usize - '1' as usize;
+                        if interact_menu.as_ref().unwrap().choose(&mut client, idx) {
+                            interact_menu = None;
+                        }
+                    }
+                    Key::Esc | Key::Char('o') => interact_menu = None,
+                    _ => {}
+                },
+                TermEvent::Key(k)
+                    if panel == Some(Panel::Inventory) && inventory_panel.is_prompting() =>
+                {
+                    let inventories = client.state().read_storage::<comp::Inventory>();
+                    if let Some(inv) = inventories.get(client.entity()) {
+                        inventory_panel.prompt_key(k, inv);
+                    }
+                }
                 TermEvent::Key(Key::Char('\n')) => chat_input_enabled = true,
-                TermEvent::Key(Key::Char('w')) => inputs.move_dir.y += 1.0,
-                TermEvent::Key(Key::Char('a')) => inputs.move_dir.x -= 1.0,
-                TermEvent::Key(Key::Char('s')) => inputs.move_dir.y -= 1.0,
-                TermEvent::Key(Key::Char('d')) => inputs.move_dir.x += 1.0,
-                TermEvent::Key(Key::Char('u')) => client.accept_invite(),
-                TermEvent::Key(Key::Char('i')) => client.decline_invite(),
-                TermEvent::Key(Key::Char('t')) => inv_toggle = !inv_toggle,
-                TermEvent::Key(Key::Down) => invpos += 1,
-                TermEvent::Key(Key::Up) => invpos -= 1,
-                TermEvent::Key(Key::Right) => match arrowedpos {
-                    0 => {
-                        arrowed1 = arrowed;
-                        arrowedpos = 1;
-                        // swap = false;
+                TermEvent::Key(
+                    k @ (Key::Up
+                    | Key::Down
+                    | Key::Left
+                    | Key::Right
+                    | Key::Esc
+                    | Key::Delete
+                    | Key::Char('m' | 'v' | 'e' | 'D' | 'o')),
+                ) if panel == Some(Panel::Inventory) => {
+                    let action = {
+                        let inventories = client.state().read_storage::<comp::Inventory>();
+                        let Some(inv) = inventories.get(client.entity()) else {
+                            continue;
+                        };
+                        match k {
+                            Key::Up => {
+                                inventory_panel.move_cursor(0, -1, inv);
+                                None
+                            }
+                            Key::Down => {
+                                inventory_panel.move_cursor(0, 1, inv);
+                                None
+                            }
+                            Key::Left => {
+                                inventory_panel.move_cursor(-1, 0, inv);
+                                None
+                            }
+                            Key::Right => {
+                                inventory_panel.move_cursor(1, 0, inv);
+                                None
+                            }
+                            Key::Char('m') => inventory_panel.select_or_move(inv),
+                            Key::Char('v') => inventory_panel.split(inv),
+                            Key::Char('e') => inventory_panel.use_item(inv),
+                            Key::Delete => inventory_panel.drop_item(inv),
+                            Key::Char('D') => inventory_panel.split_drop(inv),
+                            Key::Char('o') => inventory_panel.sort(),
+                            _ => {
+                                if !inventory_panel.cancel() {
+                                    panel = None;
+                                }
+                                None
+                            }
+                        }
+                    };
+                    if let Some(action) = action {
+                        action.apply(&mut client);
+                    }
+                }
+                // `u` is left to the invite prompt while there's an invite waiting
+                TermEvent::Key(
+                    k @ (Key::Up | Key::Down | Key::Char('e') | Key::Char('u') | Key::Esc),
+                ) if panel == Some(Panel::Loadout)
+                    && !(k == Key::Char('u') && !invite.is_empty()) =>
+                {
+                    let action = {
+                        let inventories = client.state().read_storage::<comp::Inventory>();
+                        let Some(inv) = inventories.get(client.entity()) else {
+                            continue;
+                        };
+                        match k {
+                            Key::Up => {
+                                loadout_panel.move_cursor(-1, inv);
+                                None
+                            }
+                            Key::Down => {
+                                loadout_panel.move_cursor(1, inv);
+                                None
+                            }
+                            Key::Char('e') => loadout_panel.equip(inv),
+                            Key::Char('u') => loadout_panel.unequip(inv),
+                            _ => {
+                                if !loadout_panel.cancel() {
+                                    panel = None;
+                                }
+                                None
+                            }
+                        }
+                    };
+                    if let Some(action) = action {
+                        action.apply(&mut client);
                     }
-                    1 => {
-                        arrowed2 = arrowed;
-                        arrowedpos = 2;
+                }
+                TermEvent::Key(k @ (Key::Up | Key::Down | Key::Char('c' | 'f')))
+                    if panel == Some(Panel::Crafting) =>
+                {
+                    match k {
+                        Key::Up => crafting_panel.move_cursor(-1),
+                        Key::Down => crafting_panel.move_cursor(1),
+                        Key::Char('f') => crafting_panel.toggle_filter(),
+                        _ => {
+                            if let Some(request) = crafting_panel.craft(&client) {
+                                request.apply(&mut client);
+                            }
+                        }
                     }
-                    _ => {
-                        // swap = true;
-                        arrowedpos = 2;
+                }
+                TermEvent::Key(
+                    k @ (Key::Up | Key::Down | Key::Char('\t' | '+' | '-' | 'A' | 'R' | 'y' | 'n')),
+                ) if panel == Some(Panel::Trade) => match k {
+                    Key::Up => trade_panel.move_cursor(-1),
+                    Key::Down => trade_panel.move_cursor(1),
+                    Key::Char('\t') => trade_panel.switch_side(),
+                    Key::Char(c) => {
+                        if let Some(action) = trade_panel.action(&client, c) {
+                            client.trade_action(action);
+                        }
                     }
+                    _ => {}
                 },
-                TermEvent::Key(Key::Left) => {
-                    use_slotid = arrowed;
-                    use_item = true;
+                TermEvent::Key(k @ (Key::Up | Key::Down | Key::Char('I' | 'K' | 'L' | 'X')))
+                    if panel == Some(Panel::Group) =>
+                {
+                    match k {
+                        Key::Up => group_panel.move_cursor(-1),
+                        Key::Down => group_panel.move_cursor(1),
+                        Key::Char(c) => {
+                            if let Some(action) = group_panel.action(&client, c, targeting.uid()) {
+                                group_panel.set_status(action.apply(&mut client));
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                TermEvent::Key(k @ (Key::Up | Key::Down | Key::Char('\t')))
+                    if panel == Some(Panel::Players) =>
+                {
+                    match k {
+                        Key::Up => players_panel.scroll(-1),
+                        Key::Down => players_panel.scroll(1),
+                        _ => players_panel.switch_view(),
+                    }
+                }
+                TermEvent::Key(
+                    k @ (Key::Up | Key::Down | Key::Left | Key::Right | Key::Char('u')),
+                ) if panel == Some(Panel::Skills)
+                    && !(k == Key::Char('u') && !invite.is_empty()) =>
+                {
+                    match k {
+                        Key::Up => skills_panel.move_cursor(-1),
+                        Key::Down => skills_panel.move_cursor(1),
+                        Key::Left => skills_panel.switch_group(-1),
+                        Key::Right => skills_panel.switch_group(1),
+                        _ => {
+                            if let Some(skill) = skills_panel.unlock(&client) {
+                                client.unlock_skill(skill);
+                            }
+                        }
+                    }
+                }
+                TermEvent::Key(k @ (Key::Up | Key::Down)) if panel == Some(Panel::Combat) => {
+                    combat_log.scroll(if k == Key::Up { 1 } else { -1 })
+                }
+                TermEvent::Key(Key::Esc) if panel.is_some() => panel = None,
+                TermEvent::Key(Key::Esc) if targeting.uid().is_some() => targeting.clear(),
+                TermEvent::Key(Key::Char(c @ ('w' | 'a' | 's' | 'd'))) => {
+                    // Taking manual control cancels any automatic movement
+                    travel = None;
+                    tgt_pos = None;
+                    match c {
+                        'w' => inputs.move_dir.y += 1.0,
+                        'a' => inputs.move_dir.x -= 1.0,
+                        's' => inputs.move_dir.y -= 1.0,
+                        _ => inputs.move_dir.x += 1.0,
+                    }
+                }
+                TermEvent::Key(Key::Char('t')) => toggle_panel(&mut panel, Panel::Inventory),
+                TermEvent::Key(Key::Char('l')) => toggle_panel(&mut panel, Panel::Loadout),
+                TermEvent::Key(Key::Char('C')) => toggle_panel(&mut panel, Panel::Crafting),
+                TermEvent::Key(Key::Char('T')) => toggle_panel(&mut panel, Panel::Trade),
+                TermEvent::Key(Key::Char('p')) => toggle_panel(&mut panel, Panel::Group),
+                TermEvent::Key(Key::Char('P')) => toggle_panel(&mut panel, Panel::Players),
+                TermEvent::Key(Key::Char('S')) => toggle_panel(&mut panel, Panel::Skills),
+                TermEvent::Key(Key::Char('H')) => toggle_panel(&mut panel, Panel::Combat),
+                TermEvent::Key(Key::Char('N')) => show_labels = !show_labels,
+                TermEvent::Key(Key::Char('O')) => show_facing = !show_facing,
+                TermEvent::Key(Key::Char('M')) => {
+                    show_trails = !show_trails;
+                    trails.clear();
+                }
+                TermEvent::Key(Key::Char(c @ ('u' | 'i'))) if !invite.is_empty() => {
+                    if let Some(msg) = invite.answer(&mut client, c == 'u') {
+                        chat_log.push(msg);
+                    }
+                }
+                TermEvent::Key(Key::Char('\t')) => {
+                    if let Some(msg) = targeting.cycle(&client) {
+                        chat_log.push(msg);
+                    }
                 }
                 TermEvent::Mouse(MouseEvent::Press(_, x, y)) => {
-                    tgt_pos = Some(from_screen_pos(Vec2::new(x, y), zoom_level))
+                    // Clicking on a glyph targets it rather than walking there
+                    let clicked = Vec2::new(x, y).map(|e| e.saturating_sub(1));
+                    let selected = last_glyphs
+                        .iter()
+                        .find(|(_, pos)| *pos == clicked)
+                        .and_then(|(entity, _)| targeting.select(&client, *entity));
+                    match selected {
+                        Some(msg) => chat_log.push(msg),
+                        None => {
+                            travel = None;
+                            tgt_pos = Some(from_screen_pos(Vec2::new(x, y), zoom_level))
+                        }
+                    }
                 }
                 TermEvent::Key(Key::Char(' ')) => {
-                    if is_jump_active {
-                        client.handle_input(InputKind::Jump, false, None, None);
-                        is_jump_active = false;
-                    } else {
-                        client.handle_input(InputKind::Jump, true, None, None);
-                        is_jump_active = true;
-                    }
+                    toggle_input(&mut client, &mut active_inputs, InputKind::Jump)
                 }
                 TermEvent::Key(Key::Char('x')) => {
-                    if is_primary_active {
-                        client.handle_input(InputKind::Primary, false, None, None);
-                        is_primary_active = false;
-                    } else {
-                        client.handle_input(InputKind::Primary, true, None, None);
-                        is_primary_active = true;
-                    }
+                    toggle_input(&mut client, &mut active_inputs, InputKind::Primary)
                 }
                 TermEvent::Key(Key::Char('z')) => {
-                    if is_secondary_active {
-                        client.handle_input(InputKind::Secondary, false, None, None);
-                        is_secondary_active = false;
-                    } else {
-                        client.handle_input(InputKind::Secondary, true, None, None);
-                        is_secondary_active = true;
+                    toggle_input(&mut client, &mut active_inputs, InputKind::Secondary)
+                }
+                TermEvent::Key(Key::Char('b')) => {
+                    toggle_input(&mut client, &mut active_inputs, InputKind::Block)
+                }
+                TermEvent::Key(Key::Char('F')) => {
+                    toggle_input(&mut client, &mut active_inputs, InputKind::Fly)
+                }
+                TermEvent::Key(Key::Char(c @ '1'..='5')) => {
+                    let slot = c as usize - '1' as usize;
+                    toggle_input(&mut client, &mut active_inputs, InputKind::Ability(slot))
+                }
+                TermEvent::Key(Key::Char('f')) => {
+                    client.handle_input(InputKind::Roll, true, None, None);
+                    tapped_inputs.push(InputKind::Roll);
+                }
+                TermEvent::Key(Key::Char('e')) => {
+                    if let Some(interactable) = Interactable::nearest(&client) {
+                        interactable.interact(&mut client);
+                    }
+                }
+                TermEvent::Key(Key::Char('o')) => {
+                    interact_menu = targeting
+                        .entity(&client)
+                        .and_then(|target| InteractMenu::new(&client, target))
+                        .or_else(|| InteractMenu::nearest(&client));
+                    if interact_menu.is_none() {
+                        chat_log.push("There's nobody nearby to interact with".to_string());
                     }
                 }
+                TermEvent::Key(Key::Char('c')) => client.toggle_sneak(),
+                TermEvent::Key(Key::Char('k')) => client.toggle_sit(),
+                TermEvent::Key(Key::Char('n')) => client.toggle_dance(),
+                TermEvent::Key(Key::Char('y')) => client.toggle_wield(),
+                TermEvent::Key(Key::PageUp) => move_z = if move_z > 0.0 { 0.0 } else { 1.0 },
+                TermEvent::Key(Key::PageDown) => move_z = if move_z < 0.0 { 0.0 } else { -1.0 },
                 TermEvent::Key(Key::Char('g')) => {
                     client.toggle_glide();
                     is_glide_active = !is_glide_active //do_glide = !do_glide,
                 }
-                TermEvent::Key(Key::Char('r')) => client.respawn(),
+                TermEvent::Key(Key::Char('r')) if death.is_dead() => client.respawn(),
                 TermEvent::Key(Key::Char('+')) => zoom_level /= 1.5,
                 TermEvent::Key(Key::Char('-')) => zoom_level *= 1.5,
                 TermEvent::Key(Key::Char('q')) => break 'running,
                 _ => {}
             }
         }
+        // Bring up the trade window when a trade starts, and tidy up once it's over
+        let is_trading = client.pending_trade().is_some();
+        if is_trading && !was_trading {
+            panel = Some(Panel::Trade);
+        } else if !is_trading && was_trading {
+            trade_panel.reset();
+            chat_log.push("Trade finished".to_string());
+            if panel == Some(Panel::Trade) {
+                panel = None;
+            }
+        }
+        was_trading = is_trading;
+
+        // Swim or climb up and down
+        inputs.move_z = move_z;
+        inputs.climb = if move_z > 0.0 {
+            Some(comp::Climb::Up)
+        } else if move_z < 0.0 {
+            Some(comp::Climb::Down)
+        } else {
+            None
+        };
+
+        // Steer towards the `/goto` or `/follow` target
+        if let Some(t) = &travel {
+            match t.target_pos(&client) {
+                Some(pos) => {
+                    let dist = pos.distance(Vec2::from(player_pos));
+                    if let Travel::Follow { .. } = t {
+                        if dist < FOLLOW_DIST {
+                            tgt_pos = None;
+                        } else if tgt_pos.map_or(true, |tp| tp.distance(pos) > FOLLOW_DIST) {
+                            tgt_pos = Some(pos);
+                        }
+                    } else if tgt_pos.is_none() {
+                        tgt_pos = Some(pos);
+                    }
+                }
+                None => {
+                    chat_log.push(format!("Lost track of target ({})", t.describe()));
+                    travel = None;
+                    tgt_pos = None;
+                }
+            }
+        }
+
+        let mut path_jump = false;
         if let Some(tp) = tgt_pos {
-            if tp.distance_squared(player_pos.into()) < 1.0 {
-                tgt_pos = None;
-            } else {
-                inputs.move_dir = (tp - Vec2::from(player_pos))
-                    .try_normalized()
-                    .unwrap_or_else(Vec2::zero);
+            let terrain = client.state().terrain();
+            let mut replanned = false;
+            let current = match route.take() {
+                Some(r) if r.target() == tp => r,
+                _ => {
+                    replanned = true;
+                    Route::new(&*terrain, player_pos, tp)
+                }
+            };
+            route = Some(current);
+            match route.as_mut().unwrap().steer(&*terrain, player_pos) {
+                Some((dir, jump)) => {
+                    inputs.move_dir = dir;
+                    path_jump = jump;
+                }
+                None => {
+                    route = None;
+                    let goal = match &travel {
+                        Some(Travel::Goto(goal)) => Some(*goal),
+                        _ => None,
+                    };
+                    match goal {
+                        Some(goal) if goal.distance(Vec2::from(player_pos)) < ARRIVE_DIST => {
+                            chat_log.push("Arrived".to_string());
+                            travel = None;
+                            tgt_pos = None;
+                        }
+                        // A fresh plan that goes nowhere means we're as close as we can get
+                        Some(_) if replanned => {
+                            chat_log.push("Can't get any closer".to_string());
+                            travel = None;
+                            tgt_pos = None;
+                        }
+                        // The route only went part of the way, so plan again from here
+                        Some(_) => {}
+                        None => tgt_pos = None,
+                    }
+                }
             }
+        } else {
+            route = None;
+        }
+        // Hop up ledges along the route, unless the player is already holding jump
+        if path_jump != is_path_jumping && !active_inputs.contains(&InputKind::Jump) {
+            client.handle_input(InputKind::Jump, path_jump, None, None);
+            is_path_jumping = path_jump;
+        }
+        // Keep facing the target so that attacks are aimed at it
+        if let Some(dir) = targeting.look_dir(&client) {
+            inputs.look_dir = dir;
         }
         let events = client.tick(inputs, clock.dt()).unwrap();
         let inventory_storage = client.state().ecs().read_storage::<comp::Inventory>();
         let inventory = inventory_storage.get(client.entity());
+        let split_action = inventory.and_then(|inv| inventory_panel.tick(inv));
         // Tick client
         for event in events {
-            if let Event::Chat(msg) = event {
-                match msg.chat_type {
+            match event {
+                Event::Chat(msg) => match msg.chat_type {
                     comp::ChatType::World(_) => {
                         chat_log.push(msg.content().as_plain().unwrap_or_default().to_string())
                     }
@@ -339,8 +704,19 @@ fn main() {
                         "[Group] {}",
                         msg.content().as_plain().unwrap_or_default()
                     )),
+                    comp::ChatType::Kill(ref source, victim) => {
+                        combat_log.on_kill(&client, source, victim);
+                        if let Some(line) = death.on_kill(&client, source, victim) {
+                            chat_log.push(line);
+                        }
+                    }
                     _ => {}
+                },
+                Event::Outcome(outcome) => {
+                    combat_log.on_outcome(&client, &outcome);
+                    effects.on_outcome(&outcome);
                 }
+                _ => {}
             }
         }
 
@@ -366,42 +742,19 @@ fn main() {
                     let mut block_z = 0;
                     let mut block = None;
                     let mut block_char = None;
+                    let mut sprite_style = None;
 
                     for (k, z) in (-2..16).enumerate() {
                         block_z = wpos.z - z;
 
                         if let Ok(b) = state.terrain().get(wpos + Vec3::unit_z() * -z) {
                             let sprite = b.get_sprite();
-                            if sprite.is_some() && sprite.unwrap() != SpriteKind::Empty {
-                                let sprite2 = sprite.unwrap();
-                                let flower1 =
-                                    SpriteKind::BarrelCactus as u8..=SpriteKind::Turnip as u8;
-                                let flower2 =
-                                    SpriteKind::LargeGrass as u8..=SpriteKind::LargeCactus as u8;
-                                let furniture =
-                                    SpriteKind::Window1 as u8..=SpriteKind::WardrobeDouble as u8;
-                                block_char = match sprite2 {
-                                    SpriteKind::Apple => Some('a'),
-                                    SpriteKind::Sunflower => Some('u'),
-                                    SpriteKind::Mushroom => Some('m'),
-                                    SpriteKind::Velorite | SpriteKind::VeloriteFrag => Some('v'),
-                                    SpriteKind::Chest | SpriteKind::Crate => Some('c'),
-                                    SpriteKind::Stones => Some('s'),
-                                    SpriteKind::Twigs => Some('t'),
-                                    SpriteKind::Amethyst | SpriteKind::Ruby => Some('g'), // TODO: add more
-                                    SpriteKind::Beehive => Some('b'),
-                                    _ => {
-                                        let sprite3 = sprite2 as u8;
-                                        if flower1.contains(&sprite3) || flower2.contains(&sprite3)
-                                        {
-                                            Some('%')
-                                        } else if furniture.contains(&sprite3) {
-                                            Some('&')
-                                        } else {
-                                            None
-                                        }
-                                    }
-                                };
+                            if let Some(style) = sprite
+                                .filter(|sprite| *sprite != SpriteKind::Empty)
+                                .and_then(|sprite| theme.sprite(sprite))
+                            {
+                                block_char = Some(style.glyph);
+                                sprite_style = Some(style);
                             } else if b.is_filled() {
                                 block = Some(*b);
                                 if block_char.is_none() {
@@ -430,210 +783,525 @@ fn main() {
                         block_char = Some('?');
                     }
 
-                    write!(
-                        display,
-                        "{}{}",
-                        color::Rgb(col.r, col.g, col.b).fg_string(),
-                        block_char.unwrap()
-                    )
+                    let fg = sprite_style
+                        .and_then(Style::fg_str)
+                        .unwrap_or_else(|| color::Rgb(col.r, col.g, col.b).fg_string());
+                    match sprite_style.and_then(Style::bg_str) {
+                        Some(bg) => write!(
+                            display,
+                            "{}{}{}{}",
+                            fg,
+                            bg,
+                            block_char.unwrap(),
+                            color::Bg(color::Reset)
+                        ),
+                        None => write!(display, "{}{}", fg, block_char.unwrap()),
+                    }
                     .unwrap();
                 }
             }
 
+            // Render planned route
+            if let Some(route) = &route {
+                let tgt = route.target();
+                let points = route
+                    .waypoints()
+                    .iter()
+                    .map(|wp| (Vec2::from(*wp).map(|e: i32| e as f32 + 0.5), '.'))
+                    .chain(std::iter::once((tgt, 'X')));
+                for (wpos, c) in points {
+                    let scr_pos = to_screen_pos(wpos, zoom_level);
+                    if scr_pos
+                        .map2(screen_size, |e, sz| e >= 0 && e < sz as i32)
+                        .reduce_and()
+                    {
+                        write!(
+                            display.at((scr_pos.x as u16, scr_pos.y as u16)),
+                            "{}{}",
+                            color::Yellow.fg_str(),
+                            c
+                        )
+                        .unwrap();
+                    }
+                }
+            }
+
             let objs = state.ecs().entities();
             let positions = state.ecs().read_storage::<comp::Pos>();
             let bodies = state.ecs().read_storage::<comp::Body>();
+            let velocities = state.ecs().read_storage::<comp::Vel>();
 
+            let mut glyphs = Vec::new();
+            let target = targeting.entity(&client);
             for o in objs.join() {
                 let pos = positions.get(o);
                 let body = bodies.get(o);
                 if let Some(pos) = pos {
                     if let Some(body) = body {
                         let scr_pos = to_screen_pos(Vec2::from(pos.0), zoom_level);
-                        let character = match body {
-                            Body::Humanoid(humanoid) => match humanoid.species {
-                                humanoid::Species::Danari => '@',
-                                humanoid::Species::Dwarf => '@',
-                                humanoid::Species::Elf => '@',
-                                humanoid::Species::Human => '@',
-                                humanoid::Species::Orc => '@',
-                                humanoid::Species::Draugr => '@',
-                            },
-                            Body::QuadrupedLow(_) => '4',
-                            Body::QuadrupedSmall(_) => 'q',
-                            Body::QuadrupedMedium(_) => 'Q',
-                            Body::BirdMedium(_) => 'b',
-                            Body::BirdLarge(_) => 'B',
-                            Body::FishSmall(_) => 'f',
-                            Body::FishMedium(_) => 'F',
-                            Body::BipedLarge(_) => '2',
-                            Body::BipedSmall(_) => '2',
-                            Body::Object(_) => 'o',
-                            Body::Golem(_) => 'G',
-                            Body::Dragon(_) => 'D',
-                            Body::Theropod(_) => 'T',
-                            Body::Ship(_) => 'S',
-                            Body::Arthropod(_) => 'A',
-                            Body::ItemDrop(_) => 'I',
-                            Body::Crustacean(_) => 'C',
-                            //_ => '?'
+                        let body_style = theme.body(body);
+                        let character = body_style.glyph;
+                        // Fast moving objects are projectiles, drawn pointing the way they fly
+                        let projectile = match body {
+                            Body::Object(object) => velocities
+                                .get(o)
+                                .and_then(|vel| projectile_glyph(vel.0))
+                                .map(|glyph| (glyph, projectile_color(object))),
+                            _ => None,
                         };
 
                         if scr_pos
                             .map2(screen_size, |e, sz| e >= 0 && e < sz as i32)
                             .reduce_and()
                         {
+                            let (character, mut fg) = match projectile {
+                                Some((glyph, fg)) => (glyph, fg.to_string()),
+                                // Only things without an alignment get the theme's colour
+                                None => match disposition(&client, o) {
+                                    Some(disposition) => (character, disposition.color()),
+                                    None => (
+                                        character,
+                                        body_style
+                                            .fg_str()
+                                            .unwrap_or_else(|| color::White.fg_str().to_string()),
+                                    ),
+                                },
+                            };
+                            if let Some(bg) = body_style.bg_str() {
+                                fg = format!("{}{}", fg, bg);
+                            }
+                            if Some(o) == target {
+                                fg = format!("{}{}", style::Invert, fg);
+                            }
                             write!(
                                 display.at((scr_pos.x as u16, scr_pos.y as u16)),
-                                "{}{}",
-                                color::White.fg_str(),
-                                character
+                                "{}{}{}",
+                                fg,
+                                character,
+                                style::Reset
                             )
                             .unwrap();
+                            glyphs.push((o, scr_pos.map(|e| e as u16)));
                         }
                     }
                 }
             }
-            if !inv_toggle {
-                write!(
-                    display.at((0, screen_size.y + 0)),
-                    "/------- Controls ------\\"
-                )
-                .unwrap();
-                write!(
-                    display.at((0, screen_size.y + 1)),
-                    "|  wasd/click - Move    |"
-                )
-                .unwrap();
 
-                if is_jump_active {
-                    write!(
-                        display.at((0, screen_size.y + 2)),
-                        "| SPACE  - Jump ACTIVE    |"
-                    )
-                } else {
-                    write!(
-                        display.at((0, screen_size.y + 2)),
-                        "| SPACE - Jump INACTIVE |"
-                    )
+            let occupied = glyphs.iter().map(|(_, pos)| *pos).collect::<HashSet<_>>();
+            let on_free_cell = |scr_pos: Vec2<i32>| {
+                scr_pos
+                    .map2(screen_size, |e, sz| e >= 0 && e < sz as i32)
+                    .reduce_and()
+                    && !occupied.contains(&scr_pos.map(|e| e as u16))
+            };
+            if show_trails {
+                trails.update(&client);
+                for (pos, fg) in trails.points() {
+                    let scr_pos = to_screen_pos(pos, zoom_level);
+                    if on_free_cell(scr_pos) {
+                        write!(
+                            display.at((scr_pos.x as u16, scr_pos.y as u16)),
+                            "{}.{}",
+                            fg,
+                            color::Reset.fg_str()
+                        )
+                        .unwrap();
+                    }
                 }
-                .unwrap();
+            }
+            if show_facing {
+                let oris = state.ecs().read_storage::<comp::Ori>();
+                let stats = state.ecs().read_storage::<comp::Stats>();
+                for (entity, pos) in &glyphs {
+                    let Some(ori) = oris.get(*entity).filter(|_| stats.contains(*entity)) else {
+                        continue;
+                    };
+                    if let Some((offs, c)) = facing_tick(ori) {
+                        let scr_pos = pos.map(|e| e as i32) + offs;
+                        if on_free_cell(scr_pos) {
+                            write!(
+                                display.at((scr_pos.x as u16, scr_pos.y as u16)),
+                                "{}{}{}",
+                                color::LightBlack.fg_str(),
+                                c,
+                                color::Reset.fg_str()
+                            )
+                            .unwrap();
+                        }
+                    }
+                }
+            }
 
-                if is_primary_active {
+            if show_labels {
+                for label in labels(&client, &glyphs, screen_size) {
                     write!(
-                        display.at((0, screen_size.y + 3)),
-                        "|  x - Attack1 ACTIVE   |"
-                    )
-                } else {
-                    write!(
-                        display.at((0, screen_size.y + 3)),
-                        "|  x - Attack1 INACTIVE |"
+                        display.at(label.pos),
+                        "{}{}{}",
+                        label.fg,
+                        label.text,
+                        color::Reset.fg_str()
                     )
+                    .unwrap();
                 }
-                .unwrap();
+            }
+            last_glyphs = glyphs;
 
-                if is_secondary_active {
-                    write!(
-                        display.at((0, screen_size.y + 4)),
-                        "|  z - Attack2 ACTIVE   |"
-                    )
-                } else {
+            // Explosions and beams
+            for (pos, c, fg) in effects.markers(zoom_level) {
+                let scr_pos = to_screen_pos(pos, zoom_level);
+                if scr_pos
+                    .map2(screen_size, |e, sz| e >= 0 && e < sz as i32)
+                    .reduce_and()
+                {
                     write!(
-                        display.at((0, screen_size.y + 4)),
-                        "|  z - Attack2 INACTIVE |"
+                        display.at((scr_pos.x as u16, scr_pos.y as u16)),
+                        "{}{}{}",
+                        fg,
+                        c,
+                        color::Reset.fg_str()
                     )
+                    .unwrap();
                 }
-                .unwrap();
+            }
 
-                if is_glide_active {
+            // Damage numbers drift upwards as they age
+            for (pos, age, fg, text) in combat_log.floating() {
+                let scr_pos = to_screen_pos(Vec2::from(pos), zoom_level)
+                    - Vec2::new(text.len() as i32 / 2, 1 + (age * 2.0) as i32);
+                if scr_pos.x >= 0
+                    && scr_pos.y >= 0
+                    && scr_pos.x + (text.len() as i32) <= screen_size.x as i32
+                    && scr_pos.y < screen_size.y as i32
+                {
                     write!(
-                        display.at((0, screen_size.y + 5)),
-                        "|  z - Glide ACTIVE     |"
+                        display.at((scr_pos.x as u16, scr_pos.y as u16)),
+                        "{}{}{}",
+                        fg,
+                        text,
+                        color::Reset.fg_str()
                     )
-                } else {
+                    .unwrap();
+                }
+            }
+
+            // Mark where we last died
+            if let Some(location) = death.location() {
+                let scr_pos = to_screen_pos(Vec2::from(location), zoom_level);
+                if scr_pos
+                    .map2(screen_size, |e, sz| e >= 0 && e < sz as i32)
+                    .reduce_and()
+                {
                     write!(
-                        display.at((0, screen_size.y + 5)),
-                        "|  g - Glide INACTIVE   |"
+                        display.at((scr_pos.x as u16, scr_pos.y as u16)),
+                        "{}X{}",
+                        color::Red.fg_str(),
+                        color::Reset.fg_str()
                     )
+                    .unwrap();
                 }
-                .unwrap();
+            }
+            targeting.draw(&mut display, screen_size, &client);
+            death.draw(&mut display, screen_size);
+
+            if let Some(menu) = &interact_menu {
+                let height = menu.actions().len() as u16 + 3;
+                display.draw_box((0, 0), (30, height), menu.name());
+                for (i, action) in menu.actions().iter().enumerate() {
+                    let line = format!("{} - {}", i + 1, action.name());
+                    display.text((2, 1 + i as u16), 26, color::Reset.fg_str(), &line);
+                }
+                let hint_y = height - 2;
+                display.text((2, hint_y), 26, color::LightBlack.fg_str(), "o/esc - Close");
+            }
+
+            let panel_pos = Vec2::new(screen_size.x - PANEL_SIZE.x, 0);
+            match (panel, inventory) {
+                (Some(Panel::Inventory), Some(inv)) => {
+                    inventory_panel.draw(&mut display, panel_pos, inv)
+                }
+                (Some(Panel::Loadout), Some(inv)) => {
+                    loadout_panel.draw(&mut display, panel_pos, inv)
+                }
+                (Some(Panel::Crafting), _) => crafting_panel.draw(&mut display, panel_pos, &client),
+                (Some(Panel::Trade), _) => trade_panel.draw(&mut display, panel_pos, &client),
+                (Some(Panel::Group), _) => group_panel.draw(&mut display, panel_pos, &client),
+                (Some(Panel::Players), _) => players_panel.draw(&mut display, panel_pos, &client),
+                (Some(Panel::Skills), _) => skills_panel.draw(&mut display, panel_pos, &client),
+                (Some(Panel::Combat), _) => combat_log.draw(&mut display, panel_pos),
+                _ => {}
+            }
 
+            write!(
+                display.at((0, screen_size.y + 0)),
+                "/------- Controls ------\\"
+            )
+            .unwrap();
+            write!(
+                display.at((0, screen_size.y + 1)),
+                "|  wasd/click - Move    |"
+            )
+            .unwrap();
+
+            if active_inputs.contains(&InputKind::Jump) {
                 write!(
-                    display.at((0, screen_size.y + 6)),
-                    "|      r - Respawn      |"
+                    display.at((0, screen_size.y + 2)),
+                    "| SPACE  - Jump ACTIVE    |"
                 )
-                .unwrap();
-
+            } else {
                 write!(
-                    display.at((0, screen_size.y + 7)),
-                    "|      q - Quit         |"
+                    display.at((0, screen_size.y + 2)),
+                    "| SPACE - Jump INACTIVE |"
                 )
-                .unwrap();
+            }
+            .unwrap();
 
+            if active_inputs.contains(&InputKind::Primary) {
                 write!(
-                    display.at((0, screen_size.y + 8)),
-                    "|      + - Zoom in      |"
+                    display.at((0, screen_size.y + 3)),
+                    "|  x - Attack1 ACTIVE   |"
                 )
-                .unwrap();
-
+            } else {
                 write!(
-                    display.at((0, screen_size.y + 9)),
-                    "|      - - Zoom out     |"
+                    display.at((0, screen_size.y + 3)),
+                    "|  x - Attack1 INACTIVE |"
                 )
-                .unwrap();
+            }
+            .unwrap();
 
+            if active_inputs.contains(&InputKind::Secondary) {
                 write!(
-                    display.at((0, screen_size.y + 10)),
-                    "| return - Chat         |"
+                    display.at((0, screen_size.y + 4)),
+                    "|  z - Attack2 ACTIVE   |"
                 )
-                .unwrap();
-
+            } else {
                 write!(
-                    display.at((0, screen_size.y + 11)),
-                    "|{} |",
-                    &format!("Current Health - {:.0}/{:.0}", current_health, max_health)
+                    display.at((0, screen_size.y + 4)),
+                    "|  z - Attack2 INACTIVE |"
                 )
-                .unwrap();
+            }
+            .unwrap();
 
+            if is_glide_active {
                 write!(
-                    display.at((0, screen_size.y + 12)),
-                    "|{} |",
-                    &format!("Current Energy - {:.0}/{:.0}", current_energy, max_energy)
+                    display.at((0, screen_size.y + 5)),
+                    "|  z - Glide ACTIVE     |"
                 )
-                .unwrap();
-
+            } else {
                 write!(
-                    display.at((0, screen_size.y + 13)),
-                    "|Up/Down - Navigate Inv.|"
+                    display.at((0, screen_size.y + 5)),
+                    "|  g - Glide INACTIVE   |"
                 )
-                .unwrap();
+            }
+            .unwrap();
 
+            if death.is_dead() {
                 write!(
-                    display.at((0, screen_size.y + 14)),
-                    "| Left/Right - Use/Swap |"
+                    display.at((0, screen_size.y + 6)),
+                    "|{}      r - Respawn      {}|",
+                    color::Red.fg_str(),
+                    color::Reset.fg_str()
                 )
-                .unwrap();
             } else {
+                write!(
+                    display.at((0, screen_size.y + 6)),
+                    "|                       |"
+                )
             }
+            .unwrap();
+
+            write!(
+                display.at((0, screen_size.y + 7)),
+                "|      q - Quit         |"
+            )
+            .unwrap();
+
+            write!(
+                display.at((0, screen_size.y + 8)),
+                "|      + - Zoom in      |"
+            )
+            .unwrap();
+
+            write!(
+                display.at((0, screen_size.y + 9)),
+                "|      - - Zoom out     |"
+            )
+            .unwrap();
+
+            write!(
+                display.at((0, screen_size.y + 10)),
+                "| return - Chat         |"
+            )
+            .unwrap();
+
+            write!(
+                display.at((0, screen_size.y + 11)),
+                "|  t - Inventory        |"
+            )
+            .unwrap();
+
+            write!(
+                display.at((0, screen_size.y + 12)),
+                "|  l - Equipment        |"
+            )
+            .unwrap();
+
+            write!(
+                display.at((0, screen_size.y + 13)),
+                "|  e - Interact/Pick up |"
+            )
+            .unwrap();
+
+            write!(
+                display.at((0, screen_size.y + 14)),
+                "|  o - Creature menu    |"
+            )
+            .unwrap();
+            let skill_hint = match unspent_points(&client) {
+                0 => String::new(),
+                points => format!(" ({} pts)", points),
+            };
             write!(
                 display.at((0, screen_size.y + 15)),
-                "... T - Toggle Inv ... "
+                "... t Inv, l Equip, C Craft, p Group, P Who, S Skills{}, T Trade, H Log ...{:<10}",
+                skill_hint,
+                ""
             )
             .unwrap();
-            if inviter_uid.is_some() {
+            write!(display.at((0, screen_size.y + 16)), "{:<80}", "").unwrap();
+            if let Some((fg, prompt)) = invite.prompt(&client) {
                 write!(
                     display.at((0, screen_size.y + 16)),
-                    "{:?}",
-                    &format!(
-                        "{:?} Invite from {:?}. Accept[U]/Decline[I]",
-                        invite_kind, inviter_username
-                    )
+                    "{}{}{}",
+                    fg,
+                    prompt,
+                    color::Reset.fg_str()
                 )
-            } else {
-                write!(display.at((0, screen_size.y + 16)),"                                                                                                  ")
+                .unwrap();
+            }
+
+            write!(display.at((0, screen_size.y + 17)), "{:<30}", "").unwrap();
+            if let Some(travel) = &travel {
+                write!(
+                    display.at((0, screen_size.y + 17)),
+                    "{}>> {}{}",
+                    color::Yellow.fg_str(),
+                    travel.describe(),
+                    color::Reset.fg_str()
+                )
+                .unwrap();
+            }
+
+            let stance = client
+                .current::<comp::CharacterState>()
+                .map_or("Unknown", |cs| stance_name(&cs));
+            let mut held = active_inputs
+                .iter()
+                .map(|input| match input {
+                    InputKind::Ability(slot) => format!("Ability{}", slot + 1),
+                    input => format!("{:?}", input),
+                })
+                .collect::<Vec<_>>();
+            held.sort();
+            if move_z != 0.0 {
+                held.push(if move_z > 0.0 { "Up" } else { "Down" }.to_string());
             }
+            let riding = mount_name(&client)
+                .map(|name| format!(" | Riding: {}", name))
+                .unwrap_or_default();
+            write!(
+                display.at((0, screen_size.y + 18)),
+                "{:<80}",
+                format!("Stance: {} | Held: {}{}", stance, held.join(", "), riding)
+            )
             .unwrap();
 
+            write!(display.at((0, screen_size.y + 19)), "{:<30}", "").unwrap();
+            if let Some(interactable) = Interactable::nearest(&client) {
+                write!(
+                    display.at((0, screen_size.y + 19)),
+                    "{}[e] {}{}",
+                    color::Green.fg_str(),
+                    interactable.prompt(),
+                    color::Reset.fg_str()
+                )
+                .unwrap();
+            }
+
+            // Status effects, as many as fit on one line
+            write!(display.at((0, screen_size.y + 20)), "{:<80}", "").unwrap();
+            let mut x = 0;
+            for (fg, effect) in status_effects(&client) {
+                if x + effect.len() as u16 > screen_size.x {
+                    break;
+                }
+                write!(
+                    display.at((x, screen_size.y + 20)),
+                    "{}{}{}",
+                    fg,
+                    effect,
+                    color::Reset.fg_str()
+                )
+                .unwrap();
+                x += effect.len() as u16 + 2;
+            }
+
+            // Gauges, two to a row and stretched to fit the terminal
+            let width = termion::terminal_size().map_or(screen_size.x, |(w, _)| w);
+            let gauge_width = (width.clamp(40, 160) - 1) / 2;
+            let gauges = [
+                client.current::<comp::Health>().map(|health| {
+                    (
+                        "Health",
+                        color::Bg(color::Red).to_string(),
+                        health.current(),
+                        health.maximum(),
+                    )
+                }),
+                client.current::<comp::Energy>().map(|energy| {
+                    (
+                        "Energy",
+                        color::Bg(color::Blue).to_string(),
+                        energy.current(),
+                        energy.maximum(),
+                    )
+                }),
+                client.current::<comp::Poise>().map(|poise| {
+                    (
+                        "Poise",
+                        color::Bg(color::Yellow).to_string(),
+                        poise.current(),
+                        poise.maximum(),
+                    )
+                }),
+            ];
+            for (i, gauge) in gauges.into_iter().enumerate() {
+                let pos = (
+                    (i as u16 % 2) * (gauge_width + 1),
+                    screen_size.y + 21 + i as u16 / 2,
+                );
+                match gauge {
+                    Some((label, bg, current, max)) => {
+                        display.gauge(pos, gauge_width, &bg, label, current, max)
+                    }
+                    None => display.text(pos, gauge_width, color::Reset.fg_str(), ""),
+                }
+            }
+            // There's no breath stat, so just say how far under the surface our head is
+            let submerged = client
+                .current::<comp::PhysicsState>()
+                .and_then(|physics| physics.in_liquid())
+                .zip(client.current::<Body>())
+                .filter(|(depth, body)| *depth > body.height())
+                .map_or(String::new(), |(depth, body)| {
+                    format!("Submerged {:.1}m", depth - body.height())
+                });
+            display.text(
+                (gauge_width + 1, screen_size.y + 22),
+                gauge_width,
+                color::Cyan.fg_str(),
+                &submerged,
+            );
+
             let clear = "                                                                ";
             for (i, msg) in chat_log.iter().rev().take(10).enumerate() {
                 write!(display.at((30, screen_size.y + 10 - i as u16)), "{}", clear).unwrap();
@@ -650,48 +1318,56 @@ fn main() {
 
         // Finish drawing
         display.flush();
-        //Inventory Stuff Code, has to be done at the end of the block.
-        if let Some(inv) = inventory {
-            for (itr, (invslotid, item_option)) in inv.slots_with_id().enumerate() {
-                if let Some(item) = item_option {
-                    let current = (itr as u16) + 1;
-                    if inv_toggle {
-                        if invpos as u16 == current {
-                            arrowed = Some(Slot::Inventory(invslotid));
-                            write!(
-                                display.at((0, screen_size.y + current)),
-                                "Item: {}{}",
-                                item.name(),
-                                "<--"
-                            )
-                            .unwrap();
-                        } else {
-                            write!(
-                                display.at((0, screen_size.y + current)),
-                                "Item: {}{}",
-                                item.name(),
-                                "        "
-                            )
-                            .unwrap();
-                        }
-                    } else {
-                    }
-                }
-            }
-        }
         drop(inventory_storage);
-        if let (Some(left), Some(right), Some(useid)) = (arrowed1, arrowed2, use_slotid) {
-            client.swap_slots(left, right);
-            arrowed1 = None;
-            arrowed2 = None;
-            arrowedpos = 0;
-
-            if use_item {
-                client.use_slot(useid);
-            }
+        if let Some(action) = split_action {
+            action.apply(&mut client);
         }
         client.cleanup();
         // Wait for next tick
         clock.tick();
     }
 }
+
+/// A panel drawn over the right side of the map, which takes over the navigation keys.
+#[derive(Copy, Clone, PartialEq)]
+enum Panel {
+    Inventory,
+    Loadout,
+    Crafting,
+    Trade,
+    Group,
+    Players,
+    Skills,
+    Combat,
+}
+
+/// Open `new`, or close it if it's already open.
+fn toggle_panel(panel: &mut Option<Panel>, new: Panel) {
+    *panel = if *panel == Some(new) { None } else { Some(new) };
+}
+
+/// Toggle a held input, since the terminal can't tell us when a key is released.
+fn toggle_input(client: &mut Client, active: &mut HashSet<InputKind>, input: InputKind) {
+    let pressed = active.insert(input);
+    if !pressed {
+        active.remove(&input);
+    }
+    client.handle_input(input, pressed, None, None);
+}
+
+fn stance_name(cs: &comp::CharacterState) -> &'static str {
+    use comp::CharacterState as CS;
+    match cs {
+        CS::Sit => "Sitting",
+        CS::Dance => "Dancing",
+        CS::Glide(_) => "Gliding",
+        CS::GlideWield(_) => "Glider out",
+        CS::Climb(_) => "Climbing",
+        CS::Roll(_) => "Rolling",
+        CS::BasicBlock(_) => "Blocking",
+        cs if cs.is_stealthy() => "Sneaking",
+        CS::Idle(_) => "Idle",
+        CS::Wielding(_) => "Wielding",
+        _ => "Using ability",
+    }
+}
@@ -0,0 +1,83 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use termion::color;
+use vek::*;
+use veloren_client::{Client, Join, WorldExt};
+use veloren_common::{comp, uid::Uid};
+
+/// Entities moving faster than this leave a trail.
+const TRAIL_SPEED: f32 = 8.0;
+const TRAIL_LEN: usize = 6;
+
+/// The cell next to an entity's glyph in the direction it's facing, and a glyph pointing that
+/// way. North is up on the map, so screen y is flipped.
+pub fn facing_tick(ori: &comp::Ori) -> Option<(Vec2<i32>, char)> {
+    let dir = ori.look_vec().xy();
+    if dir.magnitude_squared() < 0.01 {
+        return None;
+    }
+    let octant = (dir.y.atan2(dir.x).to_degrees().rem_euclid(360.0) / 45.0).round() as usize % 8;
+    Some(match octant {
+        0 => (Vec2::new(1, 0), '>'),
+        1 => (Vec2::new(1, -1), '/'),
+        2 => (Vec2::new(0, -1), '^'),
+        3 => (Vec2::new(-1, -1), '\\'),
+        4 => (Vec2::new(-1, 0), '<'),
+        5 => (Vec2::new(-1, 1), '/'),
+        6 => (Vec2::new(0, 1), 'v'),
+        _ => (Vec2::new(1, 1), '\\'),
+    })
+}
+
+/// Recent positions of fast moving entities, oldest first.
+#[derive(Default)]
+pub struct Trails {
+    trails: HashMap<Uid, VecDeque<Vec2<f32>>>,
+}
+
+impl Trails {
+    /// Record where everything fast is now. Trails of entities that have slowed down shrink
+    /// away, one point per update.
+    pub fn update(&mut self, client: &Client) {
+        let state = client.state();
+        let positions = state.ecs().read_storage::<comp::Pos>();
+        let velocities = state.ecs().read_storage::<comp::Vel>();
+        let uids = state.ecs().read_storage::<Uid>();
+
+        let mut moving = HashSet::new();
+        for (pos, vel, uid) in (&positions, &velocities, &uids).join() {
+            if vel.0.magnitude_squared() > TRAIL_SPEED.powi(2) {
+                let trail = self.trails.entry(*uid).or_default();
+                trail.push_back(pos.0.xy());
+                if trail.len() > TRAIL_LEN {
+                    trail.pop_front();
+                }
+                moving.insert(*uid);
+            }
+        }
+        for (uid, trail) in &mut self.trails {
+            if !moving.contains(uid) {
+                trail.pop_front();
+            }
+        }
+        self.trails.retain(|_, trail| !trail.is_empty());
+    }
+
+    pub fn clear(&mut self) {
+        self.trails.clear();
+    }
+
+    /// Every trail point, fading from dim to bright as they get more recent.
+    pub fn points(&self) -> impl Iterator<Item = (Vec2<f32>, &'static str)> + '_ {
+        self.trails.values().flat_map(|trail| {
+            let len = trail.len();
+            trail.iter().enumerate().map(move |(i, pos)| {
+                let fg = if i + 2 >= len {
+                    color::White.fg_str()
+                } else {
+                    color::LightBlack.fg_str()
+                };
+                (*pos, fg)
+            })
+        })
+    }
+}
@@ -0,0 +1,307 @@
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap},
+};
+use vek::*;
+use veloren_common::{terrain::Block, vol::ReadVol};
+
+/// The highest step we can take without jumping.
+const MAX_STEP: i32 = 1;
+/// The highest ledge we can get up by jumping.
+const MAX_JUMP: i32 = 2;
+/// The furthest we're willing to drop down in one go.
+const MAX_DROP: i32 = 6;
+/// Give up searching after expanding this many nodes.
+const MAX_ITERS: usize = 8192;
+/// Ticks without getting closer to the next waypoint before we re-plan.
+const STUCK_TICKS: u32 = 45;
+
+#[derive(Copy, Clone, PartialEq)]
+struct Node {
+    pos: Vec3<i32>,
+    cost: f32,
+}
+
+impl Eq for Node {}
+
+impl Ord for Node {
+    // Reversed so that `BinaryHeap` pops the cheapest node first
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .cost
+            .partial_cmp(&self.cost)
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+impl PartialOrd for Node {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+fn is_clear<V: ReadVol<Vox = Block>>(vol: &V, pos: Vec3<i32>) -> bool {
+    vol.get(pos).map_or(false, |b| !b.is_solid())
+}
+
+fn is_liquid<V: ReadVol<Vox = Block>>(vol: &V, pos: Vec3<i32>) -> bool {
+    vol.get(pos).map_or(false, |b| b.is_liquid())
+}
+
+/// Whether a character could stand (or swim) with their feet in `pos`.
+fn is_standable<V: ReadVol<Vox = Block>>(vol: &V, pos: Vec3<i32>) -> bool {
+    is_clear(vol, pos)
+        && is_clear(vol, pos + Vec3::unit_z())
+        && (is_liquid(vol, pos)
+            || vol
+                .get(pos - Vec3::unit_z())
+                .map_or(false, |b| b.is_solid()))
+}
+
+/// Find the position we'd end up standing in when moving into the column at `pos.xy()`.
+fn landing<V: ReadVol<Vox = Block>>(vol: &V, from: Vec3<i32>, pos: Vec2<i32>) -> Option<Vec3<i32>> {
+    // Make sure there's room to jump up before stepping across, and that nothing's in the way
+    // of the column we're stepping or dropping into
+    let has_headroom = |p: &Vec3<i32>| {
+        let top = from.z.max(p.z) + 1;
+        (from.z..=top).all(|z| is_clear(vol, Vec3::new(from.x, from.y, z)))
+            && (p.z..=top).all(|z| is_clear(vol, Vec3::new(p.x, p.y, z)))
+    };
+
+    (-MAX_DROP..=MAX_JUMP)
+        .rev()
+        .map(|dz| Vec3::new(pos.x, pos.y, from.z + dz))
+        .filter(has_headroom)
+        .find(|p| is_standable(vol, *p))
+}
+
+fn neighbors<V: ReadVol<Vox = Block>>(
+    vol: &V,
+    pos: Vec3<i32>,
+) -> impl Iterator<Item = (Vec3<i32>, f32)> + '_ {
+    const DIRS: [(i32, i32); 8] = [
+        (1, 0),
+        (-1, 0),
+        (0, 1),
+        (0, -1),
+        (1, 1),
+        (1, -1),
+        (-1, 1),
+        (-1, -1),
+    ];
+
+    DIRS.iter().filter_map(move |&(x, y)| {
+        let dir = Vec2::new(x, y);
+        // Don't cut corners diagonally
+        if x != 0 && y != 0 {
+            let side_a = pos + Vec3::new(x, 0, 0);
+            let side_b = pos + Vec3::new(0, y, 0);
+            if !(is_clear(vol, side_a) && is_clear(vol, side_a + Vec3::unit_z()))
+                || !(is_clear(vol, side_b) && is_clear(vol, side_b + Vec3::unit_z()))
+            {
+                return None;
+            }
+        }
+        let next = landing(vol, pos, Vec2::from(pos) + dir)?;
+        let mut cost = dir.map(|e| e as f32).magnitude();
+        if next.z - pos.z > MAX_STEP {
+            cost += 1.0;
+        }
+        if is_liquid(vol, next) {
+            cost *= 3.0;
+        }
+        Some((next, cost))
+    })
+}
+
+/// Search for a path from `from` to the column containing `tgt`. If the target can't be reached
+/// (e.g. it's in terrain that isn't loaded yet), the path leads as close to it as we can get.
+pub fn find_path<V: ReadVol<Vox = Block>>(
+    vol: &V,
+    from: Vec3<f32>,
+    tgt: Vec2<f32>,
+) -> Vec<Vec3<i32>> {
+    let start = from.map(|e| e.floor() as i32);
+    let goal = tgt.map(|e| e.floor() as i32);
+    let heuristic = |pos: Vec3<i32>| {
+        Vec2::from(pos)
+            .map(|e: i32| e as f32)
+            .distance(goal.map(|e| e as f32))
+    };
+
+    let mut open = BinaryHeap::new();
+    let mut came_from = HashMap::<Vec3<i32>, Vec3<i32>>::new();
+    let mut costs = HashMap::new();
+    let mut closest = (heuristic(start), start);
+
+    open.push(Node {
+        pos: start,
+        cost: heuristic(start),
+    });
+    costs.insert(start, 0.0);
+
+    for _ in 0..MAX_ITERS {
+        let Some(Node { pos, .. }) = open.pop() else {
+            break;
+        };
+
+        let h = heuristic(pos);
+        if h < closest.0 {
+            closest = (h, pos);
+        }
+        if Vec2::from(pos) == goal {
+            break;
+        }
+
+        let cost = costs[&pos];
+        for (next, step_cost) in neighbors(vol, pos) {
+            let next_cost = cost + step_cost;
+            if costs.get(&next).map_or(true, |c| next_cost < *c) {
+                costs.insert(next, next_cost);
+                came_from.insert(next, pos);
+                open.push(Node {
+                    pos: next,
+                    cost: next_cost + heuristic(next),
+                });
+            }
+        }
+    }
+
+    let mut path = vec![closest.1];
+    while let Some(prev) = came_from.get(path.last().unwrap()) {
+        path.push(*prev);
+    }
+    // The start node is where we're already standing
+    path.pop();
+    path.reverse();
+    path
+}
+
+/// A planned route to a target position that gets followed and re-planned as we go.
+pub struct Route {
+    tgt: Vec2<f32>,
+    path: Vec<Vec3<i32>>,
+    best_dist: f32,
+    stuck_ticks: u32,
+}
+
+impl Route {
+    pub fn new<V: ReadVol<Vox = Block>>(vol: &V, from: Vec3<f32>, tgt: Vec2<f32>) -> Self {
+        Self {
+            tgt,
+            path: find_path(vol, from, tgt),
+            best_dist: f32::MAX,
+            stuck_ticks: 0,
+        }
+    }
+
+    pub fn target(&self) -> Vec2<f32> {
+        self.tgt
+    }
+
+    pub fn waypoints(&self) -> &[Vec3<i32>] {
+        &self.path
+    }
+
+    /// Work out which way to move to follow the route, along with whether we need to jump.
+    /// Returns `None` once there's nowhere further to go.
+    pub fn steer<V: ReadVol<Vox = Block>>(
+        &mut self,
+        vol: &V,
+        pos: Vec3<f32>,
+    ) -> Option<(Vec2<f32>, bool)> {
+        let wpos = |p: Vec3<i32>| Vec2::from(p).map(|e: i32| e as f32 + 0.5);
+
+        // Skip past any waypoints we've already reached
+        while let Some(next) = self.path.first() {
+            if wpos(*next).distance(Vec2::from(pos)) < 0.6 && (next.z as f32 - pos.z).abs() < 1.5 {
+                self.path.remove(0);
+                self.best_dist = f32::MAX;
+                self.stuck_ticks = 0;
+            } else {
+                break;
+            }
+        }
+
+        let next = *self.path.first()?;
+        let dist = wpos(next).distance(Vec2::from(pos));
+        if dist < self.best_dist - 0.05 {
+            self.best_dist = dist;
+            self.stuck_ticks = 0;
+        } else {
+            self.stuck_ticks += 1;
+        }
+
+        // Something's in the way that wasn't there when we planned, try again
+        if self.stuck_ticks > STUCK_TICKS {
+            *self = Self::new(vol, pos, self.tgt);
+            return self.path.first().map(|next| {
+                let dir = (wpos(*next) - Vec2::from(pos)).try_normalized();
+                (dir.unwrap_or_else(Vec2::zero), true)
+            });
+        }
+
+        let dir = (wpos(next) - Vec2::from(pos))
+            .try_normalized()
+            .unwrap_or_else(Vec2::zero);
+        Some((dir, next.z as f32 - pos.z > MAX_STEP as f32))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use veloren_common::{terrain::BlockKind, vol::WriteVol, volumes::dyna::Dyna};
+
+    /// An empty volume with solid ground at z = 0.
+    fn flat() -> Dyna<Block, ()> {
+        let mut vol = Dyna::filled(Vec3::new(8, 8, 16), Block::empty(), ());
+        for x in 0..8 {
+            for y in 0..8 {
+                fill(&mut vol, Vec2::new(x, y), 0..=0);
+            }
+        }
+        vol
+    }
+
+    fn fill(vol: &mut Dyna<Block, ()>, col: Vec2<i32>, zs: impl Iterator<Item = i32>) {
+        for z in zs {
+            let rock = Block::new(BlockKind::Rock, Rgb::zero());
+            vol.set(Vec3::new(col.x, col.y, z), rock).unwrap();
+        }
+    }
+
+    #[test]
+    fn walks_across_flat_ground() {
+        let vol = flat();
+        let to = landing(&vol, Vec3::new(2, 2, 1), Vec2::new(3, 2));
+        assert_eq!(to, Some(Vec3::new(3, 2, 1)));
+    }
+
+    #[test]
+    fn jumps_up_a_ledge() {
+        let mut vol = flat();
+        fill(&mut vol, Vec2::new(3, 2), 1..=2);
+        let to = landing(&vol, Vec3::new(2, 2, 1), Vec2::new(3, 2));
+        assert_eq!(to, Some(Vec3::new(3, 2, 3)));
+    }
+
+    #[test]
+    fn drops_off_a_ledge() {
+        let mut vol = flat();
+        fill(&mut vol, Vec2::new(2, 2), 1..=5);
+        let to = landing(&vol, Vec3::new(2, 2, 6), Vec2::new(3, 2));
+        assert_eq!(to, Some(Vec3::new(3, 2, 1)));
+    }
+
+    #[test]
+    fn wont_step_through_a_wall_into_a_pocket() {
+        let mut vol = flat();
+        fill(&mut vol, Vec2::new(2, 2), 1..=5);
+        // A wall with an air pocket at z = 2..=3 that we'd otherwise drop into
+        fill(&mut vol, Vec2::new(3, 2), 1..=1);
+        fill(&mut vol, Vec2::new(3, 2), 4..=10);
+        let to = landing(&vol, Vec3::new(2, 2, 6), Vec2::new(3, 2));
+        assert_eq!(to, None);
+    }
+}
@@ -0,0 +1,156 @@
+use crate::{
+    display::{bar, Display},
+    entity::{body_name, entity_name},
+    inventory::PANEL_SIZE,
+};
+use termion::color;
+use vek::*;
+use veloren_client::{Client, Join, WorldExt};
+use veloren_common::comp::{self, Body};
+
+const LIST_ROWS: usize = 19;
+
+/// The compass direction from `from` to `to`, with north being +y.
+pub fn bearing(from: Vec2<f32>, to: Vec2<f32>) -> &'static str {
+    const DIRS: [&str; 8] = ["N", "NE", "E", "SE", "S", "SW", "W", "NW"];
+    let offs = to - from;
+    let angle = offs.x.atan2(offs.y).to_degrees().rem_euclid(360.0);
+    DIRS[((angle + 22.5) / 45.0) as usize % DIRS.len()]
+}
+
+/// One row per loaded creature or player, closest first.
+fn nearby(client: &Client) -> Vec<(f32, String, &'static str, String, Option<f32>)> {
+    let state = client.state();
+    let Some(player_pos) = state
+        .read_storage::<comp::Pos>()
+        .get(client.entity())
+        .map(|p| p.0)
+    else {
+        return Vec::new();
+    };
+    let entities = state.ecs().entities();
+    let positions = state.ecs().read_storage::<comp::Pos>();
+    let bodies = state.ecs().read_storage::<Body>();
+    let healths = state.ecs().read_storage::<comp::Health>();
+
+    let mut rows = (&entities, &positions, &bodies, healths.maybe())
+        .join()
+        .filter(|(entity, _, body, _)| {
+            *entity != client.entity() && !matches!(body, Body::ItemDrop(_))
+        })
+        .map(|(entity, pos, body, health)| {
+            (
+                pos.0.distance(player_pos),
+                entity_name(client, entity),
+                bearing(player_pos.xy(), pos.0.xy()),
+                body_name(body),
+                health.map(|health| health.current() / health.maximum().max(1.0)),
+            )
+        })
+        .collect::<Vec<_>>();
+    rows.sort_by(|a, b| a.0.total_cmp(&b.0));
+    rows
+}
+
+/// Which list the panel shows.
+#[derive(Copy, Clone, PartialEq, Default)]
+enum View {
+    #[default]
+    Online,
+    Nearby,
+}
+
+/// Scroll state for the online players and nearby entities lists.
+#[derive(Default)]
+pub struct PlayersPanel {
+    view: View,
+    scroll: usize,
+}
+
+impl PlayersPanel {
+    pub fn scroll(&mut self, dy: i32) {
+        self.scroll = (self.scroll as i32 + dy).max(0) as usize;
+    }
+
+    pub fn switch_view(&mut self) {
+        self.view = match self.view {
+            View::Online => View::Nearby,
+            View::Nearby => View::Online,
+        };
+        self.scroll = 0;
+    }
+
+    pub fn draw(&mut self, display: &mut Display, pos: Vec2<u16>, client: &Client) {
+        let inner = PANEL_SIZE.x - 4;
+        let rows: Vec<(String, String)> = match self.view {
+            View::Online => {
+                let mut players = client.player_list().values().collect::<Vec<_>>();
+                players.sort_by_key(|info| (!info.is_online, info.player_alias.to_lowercase()));
+                players
+                    .into_iter()
+                    .map(|info| {
+                        let fg = if info.is_online {
+                            color::Reset.fg_str()
+                        } else {
+                            color::LightBlack.fg_str()
+                        };
+                        let line = format!(
+                            "{} {:<24} {}",
+                            if info.is_moderator { '@' } else { ' ' },
+                            info.player_alias,
+                            if info.is_online { "online" } else { "offline" },
+                        );
+                        (fg.to_string(), line)
+                    })
+                    .collect()
+            }
+            View::Nearby => nearby(client)
+                .into_iter()
+                .map(|(dist, name, bearing, body, health)| {
+                    let health = health.map_or(String::new(), |health| bar(health, 6));
+                    let fg = if health.is_empty() {
+                        color::LightBlack.fg_str()
+                    } else {
+                        color::Reset.fg_str()
+                    };
+                    let name = name.chars().take(12).collect::<String>();
+                    let body = body.chars().take(8).collect::<String>();
+                    let line = format!(
+                        "{:>4.0} {:<2} {:<12} {:<8} {}",
+                        dist, bearing, name, body, health
+                    );
+                    (fg.to_string(), line)
+                })
+                .collect(),
+        };
+        self.scroll = self.scroll.min(rows.len().saturating_sub(LIST_ROWS));
+
+        let title = match self.view {
+            View::Online => format!(
+                "Players ({} online)",
+                client
+                    .player_list()
+                    .values()
+                    .filter(|info| info.is_online)
+                    .count()
+            ),
+            View::Nearby => format!("Nearby ({})", rows.len()),
+        };
+        display.draw_box(pos, PANEL_SIZE, &title);
+
+        for row in 0..LIST_ROWS {
+            let (fg, line) = rows
+                .get(self.scroll + row)
+                .cloned()
+                .unwrap_or_else(|| (color::Reset.fg_str().to_string(), String::new()));
+            display.text((pos.x + 2, pos.y + 1 + row as u16), inner, &fg, &line);
+        }
+
+        display.text(
+            (pos.x + 2, pos.y + PANEL_SIZE.y - 2),
+            inner,
+            color::LightBlack.fg_str(),
+            "up/down scroll  tab switch  P close",
+        );
+    }
+}
@@ -0,0 +1,193 @@
+use crate::{
+    display::{bar, Display},
+    inventory::PANEL_SIZE,
+};
+use termion::color;
+use vek::*;
+use veloren_client::Client;
+use veloren_common::comp::skillset::{
+    skills::Skill, SkillGroupKind, SkillPrerequisite, SkillSet, SKILL_GROUP_DEFS,
+};
+
+const LIST_ROWS: usize = 12;
+
+fn group_name(kind: SkillGroupKind) -> String {
+    match kind {
+        SkillGroupKind::General => "General".to_string(),
+        SkillGroupKind::Weapon(tool) => format!("{:?}", tool),
+    }
+}
+
+/// Skill groups the character can put points into, in a stable order.
+fn groups(skill_set: &SkillSet) -> Vec<SkillGroupKind> {
+    let mut groups = SKILL_GROUP_DEFS
+        .keys()
+        .copied()
+        .filter(|kind| skill_set.skill_group_accessible(*kind))
+        .collect::<Vec<_>>();
+    groups.sort_by_key(|kind| group_name(*kind));
+    groups
+}
+
+fn skills(kind: SkillGroupKind) -> Vec<Skill> {
+    SKILL_GROUP_DEFS
+        .get(&kind)
+        .map_or(Vec::new(), |def| def.skills.iter().copied().collect())
+}
+
+/// Whether a skill is already unlocked, can be unlocked now, or what's stopping it.
+fn skill_state(skill_set: &SkillSet, skill: Skill) -> (char, &'static str) {
+    if skill_set.has_skill(skill) {
+        ('*', color::Green.fg_str())
+    } else if !skill_set.prerequisites_met(skill) {
+        ('-', color::LightBlack.fg_str())
+    } else if !skill_set.sufficient_skill_points(skill) {
+        ('$', color::Yellow.fg_str())
+    } else {
+        ('+', color::Cyan.fg_str())
+    }
+}
+
+/// Cursor state for the skill screen: which group is shown and which skill is highlighted.
+#[derive(Default)]
+pub struct SkillsPanel {
+    group: usize,
+    cursor: usize,
+    status: Option<String>,
+}
+
+impl SkillsPanel {
+    pub fn move_cursor(&mut self, dy: i32) {
+        self.cursor = (self.cursor as i32 + dy).max(0) as usize;
+    }
+
+    pub fn switch_group(&mut self, dx: i32) {
+        self.group = (self.group as i32 + dx).max(0) as usize;
+        self.cursor = 0;
+    }
+
+    /// Try to unlock the highlighted skill, returning it if the unlock should be sent.
+    pub fn unlock(&mut self, client: &Client) -> Option<Skill> {
+        let skill_set = client.current::<SkillSet>()?;
+        let kind = *groups(&skill_set).get(self.group)?;
+        let skill = *skills(kind).get(self.cursor)?;
+        let (status, unlock) = match skill_state(&skill_set, skill).0 {
+            '*' => (format!("Already unlocked {:?}", skill), false),
+            '-' => ("Prerequisites not met".to_string(), false),
+            '$' => ("Not enough skill points".to_string(), false),
+            _ => (format!("Unlocking {:?}", skill), true),
+        };
+        self.status = Some(status);
+        unlock.then_some(skill)
+    }
+
+    pub fn draw(&mut self, display: &mut Display, pos: Vec2<u16>, client: &Client) {
+        let inner = PANEL_SIZE.x - 4;
+        let Some(skill_set) = client.current::<SkillSet>() else {
+            return;
+        };
+        let groups = groups(&skill_set);
+        self.group = self.group.min(groups.len().saturating_sub(1));
+        let Some(&kind) = groups.get(self.group) else {
+            display.draw_box(pos, PANEL_SIZE, "Skills");
+            return;
+        };
+        display.draw_box(pos, PANEL_SIZE, &format!("Skills: {}", group_name(kind)));
+
+        // Experience towards the next point and unspent points in this group
+        let exp = skill_set.available_experience(kind);
+        let cost = skill_set.skill_point_cost(kind);
+        let exp_line = format!(
+            "XP {} {}/{}",
+            bar(exp as f32 / cost.max(1) as f32, 14),
+            exp,
+            cost
+        );
+        let sp_line = format!(
+            "{} points to spend, {} earned",
+            skill_set.available_sp(kind),
+            skill_set.earned_sp(kind)
+        );
+        display.text(
+            (pos.x + 2, pos.y + 1),
+            inner,
+            color::Cyan.fg_str(),
+            &exp_line,
+        );
+        display.text(
+            (pos.x + 2, pos.y + 2),
+            inner,
+            color::Cyan.fg_str(),
+            &sp_line,
+        );
+
+        let skills = skills(kind);
+        self.cursor = self.cursor.min(skills.len().saturating_sub(1));
+        let scroll = self.cursor.saturating_sub(LIST_ROWS - 1);
+        for row in 0..LIST_ROWS {
+            let (fg, line) = match skills.get(scroll + row) {
+                Some(skill) => {
+                    let (mark, fg) = skill_state(&skill_set, *skill);
+                    (fg, format!("{} {:?}", mark, skill))
+                }
+                None => (color::Reset.fg_str(), String::new()),
+            };
+            let fg = if scroll + row == self.cursor {
+                format!("{}{}", color::Bg(color::Blue), fg)
+            } else {
+                fg.to_string()
+            };
+            display.text((pos.x + 2, pos.y + 4 + row as u16), inner, &fg, &line);
+        }
+
+        // What the highlighted skill needs
+        let requires = match skills
+            .get(self.cursor)
+            .and_then(|s| s.prerequisite_skills())
+        {
+            Some(SkillPrerequisite::All(skills)) => Some(("all of", skills)),
+            Some(SkillPrerequisite::Any(skills)) => Some(("one of", skills)),
+            None => None,
+        };
+        let mut lines = vec![requires.map_or("No prerequisites".to_string(), |(how, _)| {
+            format!("Requires {}:", how)
+        })];
+        if let Some((_, prereqs)) = requires {
+            let mut prereqs = prereqs.iter().collect::<Vec<_>>();
+            prereqs.sort();
+            lines.extend(prereqs.into_iter().map(|skill| {
+                let mark = if skill_set.has_skill(*skill) {
+                    '*'
+                } else {
+                    ' '
+                };
+                format!("{} {:?}", mark, skill)
+            }));
+        }
+        for (i, line) in lines.iter().take(4).enumerate() {
+            let y = pos.y + 5 + LIST_ROWS as u16 + i as u16;
+            display.text((pos.x + 2, y), inner, color::Reset.fg_str(), line);
+        }
+
+        if let Some(status) = &self.status {
+            let y = pos.y + PANEL_SIZE.y - 3;
+            display.text((pos.x + 2, y), inner, color::Green.fg_str(), status);
+        }
+        display.text(
+            (pos.x + 2, pos.y + PANEL_SIZE.y - 2),
+            inner,
+            color::LightBlack.fg_str(),
+            "left/right group  u unlock  S close",
+        );
+    }
+}
+
+/// Total unspent skill points across every group, for the HUD.
+pub fn unspent_points(client: &Client) -> u16 {
+    client.current::<SkillSet>().map_or(0, |skill_set| {
+        groups(&skill_set)
+            .into_iter()
+            .map(|kind| skill_set.available_sp(kind))
+            .sum()
+    })
+}
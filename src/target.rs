@@ -0,0 +1,172 @@
+use crate::{
+    display::Display,
+    entity::{body_name, disposition, entity_name, Disposition},
+    players::bearing,
+};
+use termion::color;
+use vek::*;
+use veloren_client::{Client, EcsEntity, Join, WorldExt};
+use veloren_common::{
+    combat,
+    comp::{self, item::MaterialStatManifest},
+    uid::Uid,
+    util::Dir,
+};
+use veloren_common_net::sync::WorldSyncExt;
+
+/// How far away hostile creatures can be and still be cycled through.
+const TARGET_RANGE: f32 = 40.0;
+const BOX_SIZE: Vec2<u16> = Vec2::new(32, 7);
+
+/// The entity the player has selected. It's kept by uid, since entity ids get reused once the
+/// entity is unloaded.
+#[derive(Default)]
+pub struct Targeting {
+    target: Option<Uid>,
+}
+
+impl Targeting {
+    pub fn uid(&self) -> Option<Uid> {
+        self.target
+    }
+
+    pub fn entity(&self, client: &Client) -> Option<EcsEntity> {
+        client.state().ecs().entity_from_uid(self.target?)
+    }
+
+    /// Forget the target once it's dead or gone.
+    pub fn update(&mut self, client: &Client) {
+        let alive = self.entity(client).map_or(false, |entity| {
+            client
+                .state()
+                .read_storage::<comp::Health>()
+                .get(entity)
+                .map_or(true, |health| !health.is_dead)
+        });
+        if !alive {
+            self.target = None;
+        }
+    }
+
+    /// Select an entity, returning a message for the chat log.
+    pub fn select(&mut self, client: &Client, entity: EcsEntity) -> Option<String> {
+        if entity == client.entity() {
+            return None;
+        }
+        self.target = Some(*client.state().read_storage::<Uid>().get(entity)?);
+        Some(format!("Targeting {}", entity_name(client, entity)))
+    }
+
+    pub fn clear(&mut self) {
+        self.target = None;
+    }
+
+    /// Move on to the next closest hostile creature in range, wrapping around to the closest.
+    pub fn cycle(&mut self, client: &Client) -> Option<String> {
+        let state = client.state();
+        let player_pos = state.read_storage::<comp::Pos>().get(client.entity())?.0;
+        let entities = state.ecs().entities();
+        let positions = state.ecs().read_storage::<comp::Pos>();
+        let healths = state.ecs().read_storage::<comp::Health>();
+
+        let mut hostiles = (&entities, &positions, &healths)
+            .join()
+            .filter(|(_, _, health)| !health.is_dead)
+            .filter(|(entity, ..)| disposition(client, *entity) == Some(Disposition::Hostile))
+            .map(|(entity, pos, _)| (entity, pos.0.distance(player_pos)))
+            .filter(|(_, dist)| *dist < TARGET_RANGE)
+            .collect::<Vec<_>>();
+        hostiles.sort_by(|(_, a), (_, b)| a.total_cmp(b));
+
+        let current = self.entity(client);
+        let next = hostiles
+            .iter()
+            .position(|(entity, _)| Some(*entity) == current)
+            .map_or(0, |i| (i + 1) % hostiles.len());
+        match hostiles.get(next) {
+            Some((entity, _)) => self.select(client, *entity),
+            None => Some("Nothing hostile nearby".to_string()),
+        }
+    }
+
+    /// The direction to look in to aim at the target.
+    pub fn look_dir(&self, client: &Client) -> Option<Dir> {
+        let positions = client.state().read_storage::<comp::Pos>();
+        let player_pos = positions.get(client.entity())?.0;
+        let target_pos = positions.get(self.entity(client)?)?.0;
+        Dir::from_unnormalized(target_pos - player_pos)
+    }
+
+    /// Draw the target's details in the bottom left corner of the map.
+    pub fn draw(&self, display: &mut Display, screen_size: Vec2<u16>, client: &Client) {
+        let Some(entity) = self.entity(client) else {
+            return;
+        };
+        let state = client.state();
+        let ecs = state.ecs();
+        let positions = ecs.read_storage::<comp::Pos>();
+        let healths = ecs.read_storage::<comp::Health>();
+        let bodies = ecs.read_storage::<comp::Body>();
+        let pos = Vec2::new(0, screen_size.y - BOX_SIZE.y);
+        let inner = BOX_SIZE.x - 4;
+
+        display.draw_box(pos, BOX_SIZE, &entity_name(client, entity));
+
+        let rating = (
+            ecs.read_storage::<comp::Inventory>().get(entity),
+            healths.get(entity),
+            ecs.read_storage::<comp::Energy>().get(entity),
+            ecs.read_storage::<comp::Poise>().get(entity),
+            ecs.read_storage::<comp::SkillSet>().get(entity),
+            bodies.get(entity),
+        );
+        let rating = match rating {
+            (Some(inv), Some(health), Some(energy), Some(poise), Some(skill_set), Some(body)) => {
+                let msm = MaterialStatManifest::load().read();
+                let rating =
+                    combat::combat_rating(inv, health, energy, poise, skill_set, *body, &msm);
+                format!("Combat rating {:.1}", rating)
+            }
+            _ => "Combat rating unknown".to_string(),
+        };
+        let (relation, fg) = match disposition(client, entity) {
+            Some(Disposition::Hostile) => ("Hostile", color::Red.fg_str()),
+            Some(Disposition::Friendly) => ("Friendly", color::Green.fg_str()),
+            Some(_) => ("Neutral", color::Yellow.fg_str()),
+            None => ("", color::Reset.fg_str()),
+        };
+        display.text((pos.x + 2, pos.y + 1), inner, fg, relation);
+        display.text(
+            (pos.x + 2, pos.y + 2),
+            inner,
+            color::Reset.fg_str(),
+            &rating,
+        );
+
+        match healths.get(entity) {
+            Some(health) => display.gauge(
+                (pos.x + 2, pos.y + 3),
+                inner,
+                &color::Bg(color::Red).to_string(),
+                "Health",
+                health.current(),
+                health.maximum(),
+            ),
+            None => display.text((pos.x + 2, pos.y + 3), inner, color::Reset.fg_str(), ""),
+        }
+
+        let body = bodies.get(entity).map_or("Unknown".to_string(), body_name);
+        let whereabouts = positions
+            .get(client.entity())
+            .zip(positions.get(entity))
+            .map_or(String::new(), |(player, target)| {
+                format!(
+                    ", {:.0}m {}",
+                    player.0.distance(target.0),
+                    bearing(player.0.xy(), target.0.xy())
+                )
+            });
+        let line = format!("{}{}", body, whereabouts);
+        display.text((pos.x + 2, pos.y + 4), inner, color::Reset.fg_str(), &line);
+    }
+}
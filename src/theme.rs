@@ -0,0 +1,330 @@
+use crate::entity::body_name;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt::Debug,
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+use strum::IntoEnumIterator;
+use termion::color;
+use veloren_common::{
+    comp::{
+        arthropod, biped_large, biped_small, bird_large, bird_medium, crustacean, dragon,
+        fish_medium, fish_small, golem, humanoid, object, quadruped_low, quadruped_medium,
+        quadruped_small, ship, theropod, Body,
+    },
+    terrain::{sprite::Category, SpriteKind},
+};
+
+/// How to draw a sprite or body. Colours are RGB; a sprite without `fg` takes the colour of the
+/// terrain under it. Creatures with an alignment are always coloured by how they stand towards
+/// the player, so a body's `fg` only applies to unaligned ones like other players and objects.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Style {
+    pub glyph: char,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fg: Option<(u8, u8, u8)>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bg: Option<(u8, u8, u8)>,
+}
+
+impl Style {
+    fn glyph(glyph: char) -> Self {
+        Self {
+            glyph,
+            fg: None,
+            bg: None,
+        }
+    }
+
+    fn colored(glyph: char, fg: (u8, u8, u8)) -> Self {
+        Self {
+            glyph,
+            fg: Some(fg),
+            bg: None,
+        }
+    }
+
+    pub fn fg_str(&self) -> Option<String> {
+        self.fg.map(|(r, g, b)| color::Rgb(r, g, b).fg_string())
+    }
+
+    pub fn bg_str(&self) -> Option<String> {
+        self.bg.map(|(r, g, b)| color::Rgb(r, g, b).bg_string())
+    }
+}
+
+/// The theme file as written on disk. Sprites are keyed by `SpriteKind` name, and bodies by body
+/// name (e.g. `QuadrupedMedium`) or body and species (e.g. `QuadrupedMedium.Wolf`).
+#[derive(Default, Serialize, Deserialize)]
+struct ThemeFile {
+    #[serde(default)]
+    sprites: BTreeMap<String, Style>,
+    #[serde(default)]
+    bodies: BTreeMap<String, Style>,
+}
+
+fn body_kind(body: &Body) -> &'static str {
+    match body {
+        Body::Humanoid(_) => "Humanoid",
+        Body::QuadrupedLow(_) => "QuadrupedLow",
+        Body::QuadrupedSmall(_) => "QuadrupedSmall",
+        Body::QuadrupedMedium(_) => "QuadrupedMedium",
+        Body::BirdMedium(_) => "BirdMedium",
+        Body::BirdLarge(_) => "BirdLarge",
+        Body::FishSmall(_) => "FishSmall",
+        Body::FishMedium(_) => "FishMedium",
+        Body::BipedLarge(_) => "BipedLarge",
+        Body::BipedSmall(_) => "BipedSmall",
+        Body::Object(_) => "Object",
+        Body::Golem(_) => "Golem",
+        Body::Dragon(_) => "Dragon",
+        Body::Theropod(_) => "Theropod",
+        Body::Ship(_) => "Ship",
+        Body::Arthropod(_) => "Arthropod",
+        Body::ItemDrop(_) => "ItemDrop",
+        Body::Crustacean(_) => "Crustacean",
+    }
+}
+
+fn default_sprite(sprite: SpriteKind) -> Style {
+    match sprite {
+        SpriteKind::Apple => Style::glyph('a'),
+        SpriteKind::Sunflower => Style::glyph('u'),
+        SpriteKind::Mushroom => Style::glyph('m'),
+        SpriteKind::Velorite | SpriteKind::VeloriteFrag => Style::glyph('v'),
+        SpriteKind::Chest | SpriteKind::Crate => Style::glyph('c'),
+        SpriteKind::Stones => Style::glyph('s'),
+        SpriteKind::Twigs => Style::glyph('t'),
+        SpriteKind::Amethyst
+        | SpriteKind::Ruby
+        | SpriteKind::Sapphire
+        | SpriteKind::Emerald
+        | SpriteKind::Topaz
+        | SpriteKind::Diamond => Style::glyph('g'),
+        SpriteKind::Beehive => Style::glyph('b'),
+        _ => match sprite.category() {
+            Category::Plant => Style::glyph('%'),
+            Category::Furniture => Style::glyph('&'),
+            Category::Resource => Style::glyph('*'),
+            Category::Lamp => Style::colored('!', (255, 220, 120)),
+            Category::Structural => Style::glyph('|'),
+            _ => Style::glyph('~'),
+        },
+    }
+}
+
+fn default_bodies() -> BTreeMap<String, Style> {
+    [
+        ("Humanoid", Style::glyph('@')),
+        ("Humanoid.Danari", Style::colored('N', (90, 140, 255))),
+        ("Humanoid.Dwarf", Style::colored('W', (255, 110, 255))),
+        ("Humanoid.Elf", Style::colored('E', (110, 255, 255))),
+        ("Humanoid.Human", Style::colored('@', (255, 255, 255))),
+        ("Humanoid.Orc", Style::colored('U', (110, 255, 110))),
+        ("Humanoid.Draugr", Style::colored('Z', (130, 130, 130))),
+        ("QuadrupedLow", Style::glyph('4')),
+        ("QuadrupedSmall", Style::glyph('q')),
+        ("QuadrupedMedium", Style::glyph('Q')),
+        ("BirdMedium", Style::glyph('b')),
+        ("BirdLarge", Style::glyph('B')),
+        ("FishSmall", Style::glyph('f')),
+        ("FishMedium", Style::glyph('F')),
+        ("BipedLarge", Style::glyph('2')),
+        ("BipedSmall", Style::glyph('2')),
+        ("Object", Style::glyph('o')),
+        ("Golem", Style::glyph('G')),
+        ("Dragon", Style::glyph('D')),
+        ("Theropod", Style::glyph('T')),
+        ("Ship", Style::glyph('S')),
+        ("Arthropod", Style::glyph('A')),
+        ("ItemDrop", Style::glyph('I')),
+        ("Crustacean", Style::glyph('C')),
+    ]
+    .into_iter()
+    .map(|(key, style)| (key.to_string(), style))
+    .collect()
+}
+
+/// Every body and body-and-species key a theme can use.
+fn known_body_keys() -> HashSet<String> {
+    fn keys<T: Debug>(kind: &str, species: &[T]) -> Vec<String> {
+        species
+            .iter()
+            .map(|species| format!("{}.{:?}", kind, species))
+            .collect()
+    }
+
+    let mut known = default_bodies().into_keys().collect::<HashSet<_>>();
+    known.insert("ItemDrop.Item".to_string());
+    known.extend(keys("Humanoid", &humanoid::ALL_SPECIES));
+    known.extend(keys("QuadrupedLow", &quadruped_low::ALL_SPECIES));
+    known.extend(keys("QuadrupedSmall", &quadruped_small::ALL_SPECIES));
+    known.extend(keys("QuadrupedMedium", &quadruped_medium::ALL_SPECIES));
+    known.extend(keys("BirdMedium", &bird_medium::ALL_SPECIES));
+    known.extend(keys("BirdLarge", &bird_large::ALL_SPECIES));
+    known.extend(keys("FishSmall", &fish_small::ALL_SPECIES));
+    known.extend(keys("FishMedium", &fish_medium::ALL_SPECIES));
+    known.extend(keys("BipedLarge", &biped_large::ALL_SPECIES));
+    known.extend(keys("BipedSmall", &biped_small::ALL_SPECIES));
+    known.extend(keys("Golem", &golem::ALL_SPECIES));
+    known.extend(keys("Dragon", &dragon::ALL_SPECIES));
+    known.extend(keys("Theropod", &theropod::ALL_SPECIES));
+    known.extend(keys("Arthropod", &arthropod::ALL_SPECIES));
+    known.extend(keys("Crustacean", &crustacean::ALL_SPECIES));
+    known.extend(keys("Object", &object::ALL_OBJECTS));
+    known.extend(keys("Ship", &ship::ALL_BODIES));
+    known
+}
+
+impl ThemeFile {
+    /// A theme with an entry for every sprite and body.
+    fn defaults() -> Self {
+        Self {
+            sprites: SpriteKind::iter()
+                .filter(|sprite| *sprite != SpriteKind::Empty)
+                .map(|sprite| (format!("{:?}", sprite), default_sprite(sprite)))
+                .collect(),
+            bodies: default_bodies(),
+        }
+    }
+}
+
+/// Glyphs and colours for sprites and bodies. A theme loaded from a RON file is reloaded whenever
+/// the file changes, and anything missing from the file uses the default theme.
+pub struct Theme {
+    path: Option<PathBuf>,
+    modified: Option<SystemTime>,
+    sprites: HashMap<SpriteKind, Style>,
+    bodies: HashMap<String, Style>,
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+impl Theme {
+    /// The built-in default theme, with no file behind it.
+    pub fn builtin() -> Self {
+        let mut theme = Self {
+            path: None,
+            modified: None,
+            sprites: HashMap::new(),
+            bodies: HashMap::new(),
+        };
+        theme.apply(ThemeFile::default());
+        theme
+    }
+
+    /// Load the theme at `path`, writing out the default theme first if there isn't one. If it
+    /// can't be loaded, the default theme is used until the file is fixed. Also returns any
+    /// problems for the chat log.
+    pub fn load(path: impl Into<PathBuf>) -> (Self, Vec<String>) {
+        let path = path.into();
+        let mut theme = Self::builtin();
+        if !path.exists() {
+            let default = ron::ser::to_string_pretty(
+                &ThemeFile::defaults(),
+                ron::ser::PrettyConfig::default(),
+            )
+            .map_err(|err| err.to_string())
+            .and_then(|default| {
+                fs::write(&path, default)
+                    .map_err(|err| format!("Couldn't write {}: {}", path.display(), err))
+            });
+            if let Err(err) = default {
+                theme.path = Some(path);
+                return (theme, vec![err]);
+            }
+        }
+        // Don't report the same broken file again until it changes
+        theme.modified = modified_time(&path);
+        theme.path = Some(path);
+        let messages = theme.reload().unwrap_or_else(|err| vec![err]);
+        (theme, messages)
+    }
+
+    /// Read the theme file again, returning a line for each entry that isn't a known sprite or
+    /// body.
+    fn reload(&mut self) -> Result<Vec<String>, String> {
+        let Some(path) = &self.path else {
+            return Ok(Vec::new());
+        };
+        let text = fs::read_to_string(path)
+            .map_err(|err| format!("Couldn't read {}: {}", path.display(), err))?;
+        let file: ThemeFile = ron::from_str(&text)
+            .map_err(|err| format!("Couldn't parse {}: {}", path.display(), err))?;
+        self.modified = modified_time(path);
+        Ok(self.apply(file))
+    }
+
+    /// Use the styles in `file`, falling back on the default theme for anything it leaves out.
+    /// Returns a line for each entry that doesn't match a sprite or body, since it won't do
+    /// anything.
+    fn apply(&mut self, file: ThemeFile) -> Vec<String> {
+        let sprite_names = SpriteKind::iter()
+            .map(|sprite| format!("{:?}", sprite))
+            .collect::<HashSet<_>>();
+        let body_keys = known_body_keys();
+        let unknown = file
+            .sprites
+            .keys()
+            .filter(|key| !sprite_names.contains(*key))
+            .map(|key| format!("Unknown sprite in theme: {}", key))
+            .chain(
+                file.bodies
+                    .keys()
+                    .filter(|key| !body_keys.contains(*key))
+                    .map(|key| format!("Unknown body in theme: {}", key)),
+            )
+            .collect();
+
+        let mut theme = ThemeFile::defaults();
+        theme.sprites.extend(file.sprites);
+        theme.bodies.extend(file.bodies);
+        self.sprites = SpriteKind::iter()
+            .filter_map(|sprite| Some((sprite, theme.sprites.get(&format!("{:?}", sprite))?)))
+            .map(|(sprite, style)| (sprite, style.clone()))
+            .collect();
+        self.bodies = theme.bodies.into_iter().collect();
+        unknown
+    }
+
+    /// Reload the theme if the file has changed since it was last loaded, returning messages
+    /// saying how that went.
+    pub fn reload_if_changed(&mut self) -> Vec<String> {
+        let Some(path) = self.path.clone() else {
+            return Vec::new();
+        };
+        let modified = modified_time(&path);
+        if modified.is_none() || modified == self.modified {
+            return Vec::new();
+        }
+        match self.reload() {
+            Ok(unknown) => std::iter::once(format!("Reloaded theme from {}", path.display()))
+                .chain(unknown)
+                .collect(),
+            Err(err) => {
+                // Don't keep complaining about the same broken file
+                self.modified = modified;
+                vec![err]
+            }
+        }
+    }
+
+    pub fn sprite(&self, sprite: SpriteKind) -> Option<&Style> {
+        self.sprites.get(&sprite)
+    }
+
+    /// The style for a body's species if the theme has one, otherwise for the body as a whole.
+    pub fn body(&self, body: &Body) -> Style {
+        let kind = body_kind(body);
+        self.bodies
+            .get(&format!("{}.{}", kind, body_name(body)))
+            .or_else(|| self.bodies.get(kind))
+            .cloned()
+            .unwrap_or_else(|| Style::glyph('?'))
+    }
+}
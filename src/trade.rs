@@ -0,0 +1,238 @@
+use crate::{
+    display::Display,
+    entity::entity_name,
+    inventory::{quality_color, PANEL_SIZE},
+};
+use termion::color;
+use vek::*;
+use veloren_client::Client;
+use veloren_common::{
+    comp::{self, inventory::slot::InvSlotId, item::Item, Inventory},
+    trade::{ReducedInventory, TradeAction, TradePhase},
+    uid::Uid,
+};
+use veloren_common_net::sync::WorldSyncExt;
+
+const OFFER_ROWS: usize = 6;
+const LIST_ROWS: usize = 9;
+
+fn items(inv: &Inventory) -> Vec<(InvSlotId, &Item)> {
+    inv.slots_with_id()
+        .filter_map(|(slot, item)| Some((slot, item.as_ref()?)))
+        .collect()
+}
+
+/// Which side of the trade the cursor is on: 0 for our inventory and 1 for theirs.
+#[derive(Default)]
+pub struct TradePanel {
+    side: usize,
+    cursor: usize,
+    status: Option<String>,
+    /// The phase the status was set in, so it can be cleared once the trade moves on.
+    phase: Option<TradePhase>,
+}
+
+impl TradePanel {
+    pub fn move_cursor(&mut self, dy: i32) {
+        self.cursor = (self.cursor as i32 + dy).max(0) as usize;
+    }
+
+    pub fn switch_side(&mut self) {
+        self.side = 1 - self.side;
+        self.cursor = 0;
+    }
+
+    /// Work out the trade action for a key press, if there's a trade going on.
+    pub fn action(&mut self, client: &Client, key: char) -> Option<TradeAction> {
+        let (_, trade, _) = client.pending_trade().as_ref()?;
+        let state = client.state();
+        let player_uid = *state.read_storage::<Uid>().get(client.entity())?;
+        let who = trade.which_party(player_uid)?;
+        let party = if self.side == 0 { who } else { 1 - who };
+        let ours = self.side == 0;
+
+        match key {
+            'y' => {
+                self.status = Some("Accepted, waiting for the other side".to_string());
+                return Some(TradeAction::Accept(trade.phase()));
+            }
+            'n' => {
+                self.status = Some("Declined".to_string());
+                return Some(TradeAction::Decline);
+            }
+            _ => {}
+        }
+
+        let entity = state.ecs().entity_from_uid(trade.parties[party])?;
+        let inventories = state.read_storage::<comp::Inventory>();
+        let (slot, item) = *items(inventories.get(entity)?).get(self.cursor)?;
+        let offered = trade.offers[party].get(&slot).copied().unwrap_or(0);
+        let (add, quantity) = match key {
+            '+' => (true, 1),
+            'A' => (true, item.amount().saturating_sub(offered)),
+            '-' => (false, 1),
+            'R' => (false, offered),
+            _ => return None,
+        };
+        if quantity == 0 {
+            return None;
+        }
+        self.status = Some(format!(
+            "{} {}x {}",
+            if add { "Offering" } else { "Removing" },
+            quantity,
+            item.name()
+        ));
+        Some(if add {
+            TradeAction::AddItem {
+                item: slot,
+                quantity,
+                ours,
+            }
+        } else {
+            TradeAction::RemoveItem {
+                item: slot,
+                quantity,
+                ours,
+            }
+        })
+    }
+
+    pub fn draw(&mut self, display: &mut Display, pos: Vec2<u16>, client: &Client) {
+        let inner = PANEL_SIZE.x - 4;
+        let col_width = inner / 2 - 1;
+        let Some((_, trade, prices)) = client.pending_trade().as_ref() else {
+            display.draw_box(pos, PANEL_SIZE, "Trade");
+            let fg = color::LightBlack.fg_str();
+            display.text((pos.x + 2, pos.y + 1), inner, fg, "No trade in progress");
+            return;
+        };
+        let state = client.state();
+        let Some(who) = state
+            .read_storage::<Uid>()
+            .get(client.entity())
+            .and_then(|uid| trade.which_party(*uid))
+        else {
+            return;
+        };
+        let entities =
+            [who, 1 - who].map(|party| state.ecs().entity_from_uid(trade.parties[party]));
+        let inventories = state.read_storage::<comp::Inventory>();
+        let invs = entities.map(|entity| entity.and_then(|entity| inventories.get(entity)));
+        let their_name = entities[1].map_or("Unknown".to_string(), |e| entity_name(client, e));
+
+        display.draw_box(pos, PANEL_SIZE, &format!("Trade with {}", their_name));
+
+        // Both offers side by side
+        for (col, party) in [who, 1 - who].into_iter().enumerate() {
+            let x = pos.x + 2 + col as u16 * (col_width + 2);
+            let accepted = if trade.accept_flags[party] {
+                " [OK]"
+            } else {
+                ""
+            };
+            let header = format!("{}{}", ["You give", "You get"][col], accepted);
+            display.text((x, pos.y + 1), col_width, color::Yellow.fg_str(), &header);
+
+            let offer = invs[col].map_or(Vec::new(), |inv| {
+                items(inv)
+                    .into_iter()
+                    .filter_map(|(slot, item)| Some((item, *trade.offers[party].get(&slot)?)))
+                    .collect()
+            });
+            for row in 0..OFFER_ROWS {
+                let (fg, line) = match offer.get(row) {
+                    Some((item, amount)) => (
+                        quality_color(item.quality()),
+                        format!("{}x {}", amount, item.name()),
+                    ),
+                    None => (color::Reset.fg_str().to_string(), String::new()),
+                };
+                display.text((x, pos.y + 2 + row as u16), col_width, &fg, &line);
+            }
+        }
+
+        // Merchants tell us what they think things are worth
+        let value_line = prices.as_ref().and_then(|prices| {
+            // `balance` wants the inventories in party order rather than ours first
+            let mut reduced = invs.map(|inv| inv.map(ReducedInventory::from));
+            if who == 1 {
+                reduced.swap(0, 1);
+            }
+            let give = prices.balance(&trade.offers, &reduced, who, true)?;
+            let get = prices.balance(&trade.offers, &reduced, 1 - who, false)?;
+            Some(format!("Value: give {:.1} / get {:.1}", give, get))
+        });
+        let value_y = pos.y + 2 + OFFER_ROWS as u16;
+        let value_line = value_line.unwrap_or_default();
+        display.text(
+            (pos.x + 2, value_y),
+            inner,
+            color::Cyan.fg_str(),
+            &value_line,
+        );
+
+        // The inventory we're picking items from
+        let list_y = value_y + 2;
+        let side_name = if self.side == 0 {
+            "Your items".to_string()
+        } else {
+            format!("{}'s items", their_name)
+        };
+        display.text(
+            (pos.x + 2, list_y - 1),
+            inner,
+            color::Yellow.fg_str(),
+            &side_name,
+        );
+        let party = if self.side == 0 { who } else { 1 - who };
+        let list = invs[self.side].map_or(Vec::new(), items);
+        self.cursor = self.cursor.min(list.len().saturating_sub(1));
+        let scroll = self.cursor.saturating_sub(LIST_ROWS - 1);
+        for row in 0..LIST_ROWS {
+            let (fg, line) = match list.get(scroll + row) {
+                Some((slot, item)) => {
+                    let offered = trade.offers[party].get(slot).copied().unwrap_or(0);
+                    let offered = if offered > 0 {
+                        format!(" ({} offered)", offered)
+                    } else {
+                        String::new()
+                    };
+                    let line = format!("{}x {}{}", item.amount(), item.name(), offered);
+                    (quality_color(item.quality()), line)
+                }
+                None => (color::Reset.fg_str().to_string(), String::new()),
+            };
+            let fg = if scroll + row == self.cursor {
+                format!("{}{}", color::Bg(color::Blue), fg)
+            } else {
+                fg
+            };
+            display.text((pos.x + 2, list_y + row as u16), inner, &fg, &line);
+        }
+
+        if self.phase != Some(trade.phase()) {
+            self.phase = Some(trade.phase());
+            self.status = None;
+        }
+        let phase = match trade.phase() {
+            TradePhase::Mutate => "Making offers",
+            TradePhase::Review => "Reviewing, accept again to confirm",
+            TradePhase::Complete => "Complete",
+        };
+        let status = self.status.as_deref().unwrap_or(phase);
+        let y = pos.y + PANEL_SIZE.y - 3;
+        display.text((pos.x + 2, y), inner, color::Green.fg_str(), status);
+        display.text(
+            (pos.x + 2, pos.y + PANEL_SIZE.y - 2),
+            inner,
+            color::LightBlack.fg_str(),
+            "+/- A/R amount  tab side  y/n accept",
+        );
+    }
+
+    /// Forget any per-trade state once a trade is over.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
@@ -0,0 +1,69 @@
+use crate::entity::{find_player, uid_name};
+use vek::*;
+use veloren_client::Client;
+use veloren_common::{comp, uid::Uid};
+use veloren_common_net::sync::WorldSyncExt;
+
+/// How close we try to stay to an entity we're following.
+pub const FOLLOW_DIST: f32 = 3.0;
+/// How close to a `/goto` destination counts as having arrived.
+pub const ARRIVE_DIST: f32 = 2.0;
+
+/// An automatic movement goal set with the `/goto` or `/follow` commands.
+pub enum Travel {
+    Goto(Vec2<f32>),
+    Follow { uid: Uid, alias: String },
+}
+
+impl Travel {
+    /// Parse a locally handled travel command, or return `None` if `argv` isn't one. `/follow`
+    /// without a name follows `target`.
+    pub fn from_command(
+        client: &Client,
+        argv: &str,
+        target: Option<Uid>,
+    ) -> Option<Result<Self, String>> {
+        let mut args = argv.split_whitespace();
+        match args.next()? {
+            // Only the two coordinate form is ours, anything else is the server's teleport
+            "/goto" => match args.map(str::parse::<f32>).collect::<Vec<_>>().as_slice() {
+                [Ok(x), Ok(y)] => Some(Ok(Travel::Goto(Vec2::new(*x, *y)))),
+                _ => None,
+            },
+            "/follow" => Some(match args.next() {
+                Some(name) => find_player(client, name)
+                    .map(|(uid, alias)| Travel::Follow { uid, alias })
+                    .ok_or_else(|| format!("No player named '{}' nearby", name)),
+                None => target
+                    .map(|uid| Travel::Follow {
+                        uid,
+                        alias: uid_name(client, uid),
+                    })
+                    .ok_or_else(|| "Usage: /follow <player>, or select a target".to_string()),
+            }),
+            _ => None,
+        }
+    }
+
+    /// The position we're currently heading towards, if the target still exists.
+    pub fn target_pos(&self, client: &Client) -> Option<Vec2<f32>> {
+        match self {
+            Travel::Goto(pos) => Some(*pos),
+            Travel::Follow { uid, .. } => {
+                let state = client.state();
+                let entity = state.ecs().entity_from_uid(*uid)?;
+                state
+                    .read_storage::<comp::Pos>()
+                    .get(entity)
+                    .map(|pos| Vec2::from(pos.0))
+            }
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Travel::Goto(pos) => format!("Travelling to {:.0}, {:.0}", pos.x, pos.y),
+            Travel::Follow { alias, .. } => format!("Following {}", alias),
+        }
+    }
+}